@@ -0,0 +1,137 @@
+//! Paint whole frames of a plot, including tessellation by egui. Run with
+//! `cargo bench --bench paint`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use eframe::egui::{pos2, vec2, CentralPanel, Color32, CtxRef, RawInput, Rect, Stroke};
+use eplot::{
+    items::{JoinStyle, Line, MarkerShape, Scatter, Value, YReference},
+    plot::{PlotCtx, PlotUi},
+};
+
+fn samples(count: usize) -> Vec<Value> {
+    (0..count)
+        .map(|i| {
+            let x = i as f64 / count as f64 * 100.;
+            Value::new(x, x.sin())
+        })
+        .collect()
+}
+
+/// Run one frame with a plot showing whatever `add_items` adds, and tessellate it.
+fn frame(ctx: &mut CtxRef, plot_ctx: &mut PlotCtx, add_items: impl FnOnce(&mut PlotUi)) {
+    ctx.begin_frame(RawInput {
+        screen_rect: Some(Rect::from_min_size(pos2(0., 0.), vec2(1920., 1080.))),
+        ..Default::default()
+    });
+    CentralPanel::default().show(ctx, |ui| {
+        plot_ctx
+            .plot("bench")
+            .size(vec2(1600., 900.))
+            .x_axis_range(0.0..=100.)
+            .y_axis_range(-1.5..=1.5)
+            .show(ui, add_items);
+    });
+    let (_, shapes) = ctx.end_frame();
+    black_box(ctx.tessellate(shapes));
+}
+
+fn paint_line(c: &mut Criterion) {
+    let (mut ctx, mut plot_ctx) = (CtxRef::default(), PlotCtx::default());
+    let points = samples(100_000);
+    c.bench_function("line 100k points", |b| {
+        b.iter(|| frame(&mut ctx, &mut plot_ctx, |plot_ui| plot_ui.add(Line::new(&points))))
+    });
+}
+
+fn paint_scatter(c: &mut Criterion) {
+    let (mut ctx, mut plot_ctx) = (CtxRef::default(), PlotCtx::default());
+    let points = samples(50_000);
+    let mut group = c.benchmark_group("scatter 50k points");
+    group.sample_size(20);
+    [
+        MarkerShape::Circle,
+        MarkerShape::Triangle,
+        MarkerShape::Square,
+        MarkerShape::Plus,
+        MarkerShape::X,
+        MarkerShape::Star,
+    ]
+    .iter()
+    .for_each(|shape| {
+        group.bench_function(&format!("{:?}", shape), |b| {
+            b.iter(|| {
+                frame(&mut ctx, &mut plot_ctx, |plot_ui| {
+                    plot_ui.add(
+                        Scatter::new(&points)
+                            .shape(*shape)
+                            .stroke(Stroke::new(1., Color32::RED))
+                            .lod(false),
+                    )
+                })
+            })
+        });
+    });
+    group.bench_function("level of detail", |b| {
+        b.iter(|| frame(&mut ctx, &mut plot_ctx, |plot_ui| plot_ui.add(Scatter::new(&points))))
+    });
+    group.finish();
+}
+
+fn paint_area_fill(c: &mut Criterion) {
+    let (mut ctx, mut plot_ctx) = (CtxRef::default(), PlotCtx::default());
+    let points = samples(10_000);
+    c.bench_function("area fill 10k points", |b| {
+        b.iter(|| {
+            frame(&mut ctx, &mut plot_ctx, |plot_ui| {
+                plot_ui.add(
+                    Line::new(&points)
+                        .area_fill(YReference::Constant(0.), Color32::from_white_alpha(40))
+                        .unwrap(),
+                )
+            })
+        })
+    });
+}
+
+/// A million points without downsampling, so every segment ends up in the mesh. With the
+/// `rayon` feature the meshes are built in parallel.
+fn paint_million_points(c: &mut Criterion) {
+    let (mut ctx, mut plot_ctx) = (CtxRef::default(), PlotCtx::default());
+    let points = samples(1_000_000);
+    let mut group = c.benchmark_group("1M points");
+    group.sample_size(10);
+    group.bench_function("line", |b| {
+        b.iter(|| {
+            frame(&mut ctx, &mut plot_ctx, |plot_ui| {
+                plot_ui.add(
+                    Line::new(&points)
+                        .weight(2.)
+                        .join_style(JoinStyle::Round)
+                        .downsample(false),
+                )
+            })
+        })
+    });
+    group.bench_function("area fill", |b| {
+        b.iter(|| {
+            frame(&mut ctx, &mut plot_ctx, |plot_ui| {
+                plot_ui.add(
+                    Line::new(&points)
+                        .area_fill(YReference::Constant(0.), Color32::from_white_alpha(40))
+                        .unwrap()
+                        .downsample(false),
+                )
+            })
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    paint_line,
+    paint_scatter,
+    paint_area_fill,
+    paint_million_points
+);
+criterion_main!(benches);
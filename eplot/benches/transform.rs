@@ -0,0 +1,33 @@
+//! Compare with and without parallel transformation:
+//! `cargo bench --bench transform` and `cargo bench --bench transform --features rayon`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use eframe::egui::{pos2, Rect};
+use eplot::{
+    items::{PlotBounds, Value},
+    plot::ScreenTransform,
+};
+
+fn transform_points(c: &mut Criterion) {
+    let points: Vec<Value> = (0..1_000_000)
+        .map(|i| {
+            let x = i as f64 / 1000.;
+            Value::new(x, x.sin())
+        })
+        .collect();
+    let transform = ScreenTransform::new(
+        Rect::from_min_max(pos2(0., 0.), pos2(1920., 1080.)),
+        PlotBounds::from_x_y_ranges(0.0..=1000., -1.0..=1.),
+    );
+
+    c.bench_function("transform 1M points", |b| {
+        b.iter(|| transform.positions(black_box(&points)))
+    });
+    let mut buffer = Vec::new();
+    c.bench_function("transform 1M points into a buffer", |b| {
+        b.iter(|| transform.positions_into(black_box(&points), &mut buffer))
+    });
+}
+
+criterion_group!(benches, transform_points);
+criterion_main!(benches);
@@ -1,14 +1,527 @@
-use eframe::egui::*;
+use super::plot::{is_sorted_by_x, ScreenTransform};
+use super::ticks::{format_tick, multiples, tick_increment, MAX_TICKS};
+use eframe::egui::{
+    epaint::{CircleShape, Mesh, RectShape},
+    *,
+};
+use std::sync::Arc;
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt,
+    ops::{Deref, Range, RangeInclusive},
+};
+
+/// Error returned when the data handed to an item is inconsistent.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ItemError {
+    /// The number of reference values, or of y values, does not match the number of points.
+    LengthMismatch { expected: usize, found: usize },
+}
+
+impl fmt::Display for ItemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LengthMismatch { expected, found } => write!(
+                f,
+                "expected {} values to match the data, found {}",
+                expected, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ItemError {}
+
+/// A point in plot coordinates. Kept in `f64` so that e.g. timestamps or a zoom deep into the
+/// data don't lose precision, it is only turned into `f32` screen coordinates when painting.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Value {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Value {
+    pub fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.x.is_finite() && self.y.is_finite()
+    }
+
+    /// The point between `self` (`t = 0`) and `other` (`t = 1`).
+    pub fn lerp(&self, other: Value, t: f64) -> Value {
+        Value::new(
+            self.x + t * (other.x - self.x),
+            self.y + t * (other.y - self.y),
+        )
+    }
+
+    pub fn to_pos2(self) -> Pos2 {
+        pos2(self.x as f32, self.y as f32)
+    }
+}
+
+impl From<Pos2> for Value {
+    fn from(pos: Pos2) -> Self {
+        Self::new(pos.x as f64, pos.y as f64)
+    }
+}
+
+impl From<(f64, f64)> for Value {
+    fn from((x, y): (f64, f64)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+/// Anything that can be used as a point in plot coordinates, so items can take the data in the
+/// form it is already stored in.
+pub trait IntoPlotPoint {
+    fn into_plot_point(self) -> Value;
+}
+
+impl IntoPlotPoint for Value {
+    fn into_plot_point(self) -> Value {
+        self
+    }
+}
+
+impl IntoPlotPoint for Pos2 {
+    fn into_plot_point(self) -> Value {
+        self.into()
+    }
+}
+
+impl IntoPlotPoint for (f64, f64) {
+    fn into_plot_point(self) -> Value {
+        self.into()
+    }
+}
+
+impl IntoPlotPoint for (f32, f32) {
+    fn into_plot_point(self) -> Value {
+        Value::new(self.0 as f64, self.1 as f64)
+    }
+}
+
+impl IntoPlotPoint for [f64; 2] {
+    fn into_plot_point(self) -> Value {
+        Value::new(self[0], self[1])
+    }
+}
+
+impl IntoPlotPoint for [f32; 2] {
+    fn into_plot_point(self) -> Value {
+        Value::new(self[0] as f64, self[1] as f64)
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+impl<T: nalgebra::Scalar + Into<f64>> IntoPlotPoint for nalgebra::Vector2<T> {
+    fn into_plot_point(self) -> Value {
+        Value::new(self[0].clone().into(), self[1].clone().into())
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+impl<T: nalgebra::Scalar + Into<f64>> IntoPlotPoint for nalgebra::Point2<T> {
+    fn into_plot_point(self) -> Value {
+        Value::new(self[0].clone().into(), self[1].clone().into())
+    }
+}
+
+/// A row of two values, e.g. from `Array2::rows`.
+#[cfg(feature = "ndarray")]
+impl<T: Copy + Into<f64>> IntoPlotPoint for ndarray::ArrayView1<'_, T> {
+    fn into_plot_point(self) -> Value {
+        Value::new(self[0].into(), self[1].into())
+    }
+}
+
+/// The points of an item, either owned or borrowed. Borrowing avoids copying a large dataset
+/// that doesn't change into a new item every frame.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlotPoints<'a>(Cow<'a, [Value]>);
+
+impl PlotPoints<'_> {
+    /// Copy borrowed points, so the item can outlive the data.
+    pub fn into_owned(self) -> PlotPoints<'static> {
+        PlotPoints(Cow::Owned(self.0.into_owned()))
+    }
+
+    /// Pair up separate x and y values, which need to have the same length.
+    pub fn from_xy<X, Y>(xs: &[X], ys: &[Y]) -> Result<Self, ItemError>
+    where
+        X: Copy + Into<f64>,
+        Y: Copy + Into<f64>,
+    {
+        if xs.len() != ys.len() {
+            return Err(ItemError::LengthMismatch {
+                expected: xs.len(),
+                found: ys.len(),
+            });
+        }
+        let points = xs
+            .iter()
+            .zip(ys)
+            .map(|(x, y)| Value::new((*x).into(), (*y).into()))
+            .collect();
+        Ok(Self(Cow::Owned(points)))
+    }
+
+    /// The y values at x = 0, 1, 2, …
+    pub fn from_ys<Y: Copy + Into<f64>>(ys: &[Y]) -> Self {
+        Self::from_ys_dx(ys, 0., 1.)
+    }
+
+    /// Uniformly sampled y values, the first one at `x0` and the following ones `dx` apart.
+    pub fn from_ys_dx<Y: Copy + Into<f64>>(ys: &[Y], x0: f64, dx: f64) -> Self {
+        let points = ys
+            .iter()
+            .enumerate()
+            .map(|(i, y)| Value::new(x0 + i as f64 * dx, (*y).into()))
+            .collect();
+        Self(Cow::Owned(points))
+    }
+}
+
+impl Deref for PlotPoints<'_> {
+    type Target = [Value];
+
+    fn deref(&self) -> &[Value] {
+        &self.0
+    }
+}
+
+impl<T: IntoPlotPoint> From<Vec<T>> for PlotPoints<'_> {
+    fn from(points: Vec<T>) -> Self {
+        points.into_iter().collect()
+    }
+}
+
+impl<T: IntoPlotPoint, const N: usize> From<[T; N]> for PlotPoints<'_> {
+    fn from(points: [T; N]) -> Self {
+        IntoIterator::into_iter(points).collect()
+    }
+}
+
+/// Other iterators of points can be collected into `PlotPoints`, e.g. to hand them to an item.
+impl<T: IntoPlotPoint> std::iter::FromIterator<T> for PlotPoints<'_> {
+    fn from_iter<I: IntoIterator<Item = T>>(points: I) -> Self {
+        Self(Cow::Owned(
+            points
+                .into_iter()
+                .map(IntoPlotPoint::into_plot_point)
+                .collect(),
+        ))
+    }
+}
+
+impl<'a> From<&'a [Value]> for PlotPoints<'a> {
+    fn from(points: &'a [Value]) -> Self {
+        Self(Cow::Borrowed(points))
+    }
+}
+
+impl<'a> From<&'a Vec<Value>> for PlotPoints<'a> {
+    fn from(points: &'a Vec<Value>) -> Self {
+        Self(Cow::Borrowed(points))
+    }
+}
+
+/// A rectangle in plot coordinates, like `Rect` but in `f64`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlotBounds {
+    pub min: Value,
+    pub max: Value,
+}
+
+impl PlotBounds {
+    /// Contains nothing, grows with `extend_with`.
+    pub const NOTHING: Self = Self {
+        min: Value {
+            x: f64::INFINITY,
+            y: f64::INFINITY,
+        },
+        max: Value {
+            x: f64::NEG_INFINITY,
+            y: f64::NEG_INFINITY,
+        },
+    };
+
+    pub fn from_min_max(min: Value, max: Value) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_x_y_ranges(x_range: RangeInclusive<f64>, y_range: RangeInclusive<f64>) -> Self {
+        Self {
+            min: Value::new(*x_range.start(), *y_range.start()),
+            max: Value::new(*x_range.end(), *y_range.end()),
+        }
+    }
+
+    pub fn x_range(&self) -> RangeInclusive<f64> {
+        self.min.x..=self.max.x
+    }
+
+    pub fn y_range(&self) -> RangeInclusive<f64> {
+        self.min.y..=self.max.y
+    }
+
+    pub fn width(&self) -> f64 {
+        self.max.x - self.min.x
+    }
+
+    pub fn height(&self) -> f64 {
+        self.max.y - self.min.y
+    }
+
+    pub fn center(&self) -> Value {
+        self.min.lerp(self.max, 0.5)
+    }
+
+    pub fn contains(&self, value: Value) -> bool {
+        self.x_range().contains(&value.x) && self.y_range().contains(&value.y)
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.min.is_finite() && self.max.is_finite()
+    }
+
+    pub fn extend_with(&mut self, value: Value) {
+        self.min.x = self.min.x.min(value.x);
+        self.min.y = self.min.y.min(value.y);
+        self.max.x = self.max.x.max(value.x);
+        self.max.y = self.max.y.max(value.y);
+    }
+}
+
+impl From<Rect> for PlotBounds {
+    fn from(rect: Rect) -> Self {
+        Self::from_min_max(rect.min.into(), rect.max.into())
+    }
+}
+
+/// Collects the shapes of the items instead of adding them to the painter right away, so the
+/// plot can cache them, see `Plot::data_version`.
+pub struct PlotPainter<'p> {
+    painter: &'p Painter,
+    shapes: Vec<Shape>,
+    /// Buffers handed back with `recycle`, see `scratch`.
+    scratch: Vec<Vec<Pos2>>,
+}
+
+impl<'p> PlotPainter<'p> {
+    /// Adds the shapes to `shapes`, which should be empty.
+    pub(crate) fn new(painter: &'p Painter, shapes: Vec<Shape>, scratch: Vec<Vec<Pos2>>) -> Self {
+        Self {
+            painter,
+            shapes,
+            scratch,
+        }
+    }
+
+    /// The shapes added so far, and the buffers to reuse in the next frame.
+    pub(crate) fn into_parts(self) -> (Vec<Shape>, Vec<Vec<Pos2>>) {
+        (self.shapes, self.scratch)
+    }
+
+    /// An empty buffer for screen positions. Hand it back with `recycle` once it is no longer
+    /// needed. The plot keeps these buffers between frames, so building geometry with them
+    /// doesn't allocate every frame.
+    pub fn scratch(&mut self) -> Vec<Pos2> {
+        self.scratch.pop().unwrap_or_default()
+    }
+
+    /// Return a buffer for reuse, see `scratch`.
+    pub fn recycle(&mut self, mut buffer: Vec<Pos2>) {
+        buffer.clear();
+        self.scratch.push(buffer);
+    }
+
+    pub fn clip_rect(&self) -> Rect {
+        self.painter.clip_rect()
+    }
+
+    /// The width of the fringe egui fades the edges of shapes out over, or zero without
+    /// anti-aliasing.
+    pub fn feathering(&self) -> f32 {
+        let ctx = self.painter.ctx();
+        if ctx.memory().options.tessellation_options.anti_alias {
+            1. / ctx.pixels_per_point()
+        } else {
+            0.
+        }
+    }
+
+    pub fn layout_no_wrap(
+        &self,
+        text: String,
+        text_style: TextStyle,
+        color: Color32,
+    ) -> Arc<Galley> {
+        self.painter.layout_no_wrap(text, text_style, color)
+    }
+
+    pub fn add(&mut self, shape: impl Into<Shape>) {
+        self.shapes.push(shape.into());
+    }
+
+    pub fn line_segment(&mut self, points: [Pos2; 2], stroke: impl Into<Stroke>) {
+        self.add(Shape::line_segment(points, stroke));
+    }
+
+    pub fn circle(
+        &mut self,
+        center: Pos2,
+        radius: f32,
+        fill: impl Into<Color32>,
+        stroke: impl Into<Stroke>,
+    ) {
+        self.add(CircleShape {
+            center,
+            radius,
+            fill: fill.into(),
+            stroke: stroke.into(),
+        });
+    }
+
+    pub fn rect(
+        &mut self,
+        rect: Rect,
+        corner_radius: f32,
+        fill: impl Into<Color32>,
+        stroke: impl Into<Stroke>,
+    ) {
+        self.add(RectShape {
+            rect,
+            corner_radius,
+            fill: fill.into(),
+            stroke: stroke.into(),
+        });
+    }
+
+    pub fn rect_stroke(&mut self, rect: Rect, corner_radius: f32, stroke: impl Into<Stroke>) {
+        self.add(Shape::rect_stroke(rect, corner_radius, stroke));
+    }
+
+    /// An arrow from `origin` to `origin + vec`, like `Painter::arrow`.
+    pub fn arrow(&mut self, origin: Pos2, vec: Vec2, stroke: Stroke) {
+        let rot = emath::Rot2::from_angle(std::f32::consts::TAU / 10.);
+        let tip = origin + vec;
+        let tip_length = vec.length() / 4.;
+        let dir = vec.normalized();
+        self.line_segment([origin, tip], stroke);
+        self.line_segment([tip, tip - tip_length * (rot * dir)], stroke);
+        self.line_segment([tip, tip - tip_length * (rot.inverse() * dir)], stroke);
+    }
+
+    pub fn galley(&mut self, pos: Pos2, galley: Arc<Galley>) {
+        if !galley.is_empty() {
+            self.add(Shape::galley(pos, galley));
+        }
+    }
+
+    /// Lay out and add some text, like `Painter::text`. Returns where the text ended up.
+    pub fn text(
+        &mut self,
+        pos: Pos2,
+        anchor: Align2,
+        text: impl ToString,
+        text_style: TextStyle,
+        color: Color32,
+    ) -> Rect {
+        let galley = self.layout_no_wrap(text.to_string(), text_style, color);
+        let rect = anchor.anchor_rect(Rect::from_min_size(pos, galley.size()));
+        self.galley(rect.min, galley);
+        rect
+    }
+}
 
 /// Trait shared by everything that can be plotted.
 pub trait PlotItem {
     /// Function to turn the drawable item into Shapes.
-    fn paint(self, painter: &mut Painter, transform: &dyn Fn(&Pos2) -> Pos2);
+    fn paint(&self, painter: &mut PlotPainter, transform: &ScreenTransform);
+
+    /// The data points of the item, used to follow and fit the view to the data.
+    fn points(&self) -> &[Value] {
+        &[]
+    }
+
+    /// The area the item covers beyond its `points`, e.g. the shading of a band. Following and
+    /// fitting the view include it.
+    fn bounds(&self) -> Option<PlotBounds> {
+        None
+    }
+
+    /// Whether the x values of `points` never decrease, so the points near the pointer can be
+    /// found by binary search.
+    fn sorted_by_x(&self) -> bool {
+        is_sorted_by_x(self.points())
+    }
+
+    /// Named items are listed in the legend.
+    fn name(&self) -> Option<&str> {
+        None
+    }
+
+    /// The color shown next to the name in the legend.
+    fn color(&self) -> Color32 {
+        Color32::TRANSPARENT
+    }
+
+    /// Make the item stand out, e.g. while its legend entry is hovered.
+    fn highlight(&mut self) {}
+
+    /// Items with an id can be picked by clicking their points, see
+    /// `PlotResponse::selected_points`.
+    fn id(&self) -> Option<Id> {
+        None
+    }
+
+    /// Distance in pixels from a point within which a click picks it.
+    fn pick_radius(&self) -> f32 {
+        5.
+    }
+
+    /// Which coordinates of the points can be changed by dragging them, if any. See
+    /// `PlotResponse::dragged_point`.
+    fn drag_axes(&self) -> Option<DragAxes> {
+        None
+    }
+
+    /// A horizontal (`DragAxes::Y`) or vertical (`DragAxes::X`) line at a value that can be
+    /// dragged as a whole, see `PlotResponse::dragged_line`.
+    fn drag_line(&self) -> Option<(Id, DragAxes, f64)> {
+        None
+    }
+
+    /// The id of the item if `pointer`, in screen coordinates, is over it. See
+    /// `PlotResponse::hovered_item`.
+    fn hit_test(&self, _pointer: Pos2, _transform: &ScreenTransform) -> Option<Id> {
+        None
+    }
+
+    /// Called with the visible part of the plot, in plot coordinates, before the item is
+    /// painted, so it can skip what is out of view.
+    fn cull(&mut self, _bounds: PlotBounds) {}
+}
+
+/// Distance in pixels from a line within which the pointer is over it.
+const LINE_HIT_DISTANCE: f32 = 3.;
+
+/// The coordinates of a point that can be changed by dragging it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DragAxes {
+    Both,
+    X,
+    Y,
 }
 
 /// Text positioned on the plot.
 pub struct Text {
-    position: Pos2,
+    position: Value,
     _rotation: f32,
     text: String,
     color: Color32,
@@ -16,9 +529,9 @@ pub struct Text {
 }
 
 impl Text {
-    pub fn new(position: Pos2, text: impl Into<String>) -> Self {
+    pub fn new(position: impl IntoPlotPoint, text: impl Into<String>) -> Self {
         Self {
-            position,
+            position: position.into_plot_point(),
             _rotation: 0.,
             text: text.into(),
             color: Color32::WHITE,
@@ -43,7 +556,7 @@ impl Text {
 }
 
 impl PlotItem for Text {
-    fn paint(self, painter: &mut Painter, transform: &dyn Fn(&Pos2) -> Pos2) {
+    fn paint(&self, painter: &mut PlotPainter, transform: &ScreenTransform) {
         let Text {
             position,
             _rotation,
@@ -53,32 +566,151 @@ impl PlotItem for Text {
         } = self;
 
         painter.text(
-            transform(&position),
-            anchor,
+            transform.position(position),
+            *anchor,
             text,
             TextStyle::Monospace,
-            color,
+            *color,
         );
     }
 }
 
+/// Hatch pattern drawn inside a `Polygon`. The spacing is given in pixels.
+#[derive(Debug, Clone, Copy)]
+pub enum HatchStyle {
+    Diagonal { spacing_px: f32, stroke: Stroke },
+    CrossHatch { spacing_px: f32, stroke: Stroke },
+    Horizontal { spacing_px: f32, stroke: Stroke },
+    Vertical { spacing_px: f32, stroke: Stroke },
+}
+
+impl HatchStyle {
+    /// The angles of the hatch lines, the spacing and the stroke.
+    fn lines(&self) -> (&'static [f32], f32, Stroke) {
+        use std::f32::consts::FRAC_PI_4;
+        match *self {
+            Self::Diagonal { spacing_px, stroke } => (&[FRAC_PI_4], spacing_px, stroke),
+            Self::CrossHatch { spacing_px, stroke } => {
+                (&[FRAC_PI_4, -FRAC_PI_4], spacing_px, stroke)
+            }
+            Self::Horizontal { spacing_px, stroke } => (&[0.], spacing_px, stroke),
+            Self::Vertical { spacing_px, stroke } => {
+                (&[std::f32::consts::FRAC_PI_2], spacing_px, stroke)
+            }
+        }
+    }
+}
+
+fn dot(a: Vec2, b: Vec2) -> f32 {
+    a.x * b.x + a.y * b.y
+}
+
+/// Distance of `p` from the segment between `a` and `b`.
+fn segment_distance(p: Pos2, a: Pos2, b: Pos2) -> f32 {
+    let ab = b - a;
+    let length_sq = dot(ab, ab);
+    let t = if length_sq > 0. {
+        (dot(p - a, ab) / length_sq).clamp(0., 1.)
+    } else {
+        0.
+    };
+    p.distance(a + t * ab)
+}
+
+/// Whether `p` lies inside the polygon, by the even-odd rule.
+fn polygon_contains(polygon: &[Pos2], p: Pos2) -> bool {
+    let edges = polygon.iter().zip(polygon.iter().cycle().skip(1));
+    edges
+        .filter(|(a, b)| (a.y > p.y) != (b.y > p.y))
+        .filter(|(a, b)| p.x < a.x + (p.y - a.y) / (b.y - a.y) * (b.x - a.x))
+        .count()
+        % 2
+        == 1
+}
+
+/// The smallest and largest offset of the points along `normal`.
+fn offset_range(points: impl Iterator<Item = Pos2>, normal: Vec2) -> (f32, f32) {
+    points
+        .map(|p| dot(p.to_vec2(), normal))
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), offset| {
+            (min.min(offset), max.max(offset))
+        })
+}
+
+/// Clip parallel lines at the given angle and spacing against a (possibly concave) polygon.
+/// Uses the even-odd rule, so the result is correct for any simple polygon. Only the lines that
+/// cross `clip` are generated, so zooming into a large polygon keeps the visible part hatched.
+fn hatch_segments(polygon: &[Pos2], angle: f32, spacing: f32, clip: Rect) -> Vec<[Pos2; 2]> {
+    let mut segments = Vec::new();
+    if polygon.len() < 3 || spacing.is_nan() || spacing <= 0. {
+        return segments;
+    }
+    let direction = Vec2::angled(angle);
+    let normal = direction.rot90();
+    let (polygon_min, polygon_max) = offset_range(polygon.iter().copied(), normal);
+    let corners = [
+        clip.left_top(),
+        clip.right_top(),
+        clip.left_bottom(),
+        clip.right_bottom(),
+    ];
+    let (clip_min, clip_max) = offset_range(IntoIterator::into_iter(corners), normal);
+    let (min, max) = (polygon_min.max(clip_min), polygon_max.min(clip_max));
+
+    // Never emit more lines than there are pixels.
+    let max_lines = 10_000;
+    let first = (min / spacing).ceil();
+    let mut crossings = Vec::new();
+    (0..max_lines)
+        .map(|i| (first + i as f32) * spacing)
+        .take_while(|offset| *offset <= max)
+        .for_each(|offset| {
+            crossings.clear();
+            let edges = polygon.iter().zip(polygon.iter().cycle().skip(1));
+            edges.for_each(|(a, b)| {
+                let da = dot(a.to_vec2(), normal) - offset;
+                let db = dot(b.to_vec2(), normal) - offset;
+                if (da > 0.) != (db > 0.) {
+                    let q = *a + da / (da - db) * (*b - *a);
+                    crossings.push(dot(q.to_vec2(), direction));
+                }
+            });
+            crossings.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+            crossings.chunks_exact(2).for_each(|c| {
+                let base = (offset * normal).to_pos2();
+                segments.push([base + c[0] * direction, base + c[1] * direction]);
+            });
+        });
+    segments
+}
+
 /// A closed line. The first and last points are connected automatically.
 /// Non-convex shapes may lead to unexpected results when `fill` is enabled.
-pub struct Polygon {
-    points: Vec<Pos2>,
+pub struct Polygon<'a> {
+    points: PlotPoints<'a>,
     fill: Color32,
     stroke: Stroke,
+    hatch: Option<HatchStyle>,
+    id: Option<Id>,
 }
 
-impl Polygon {
-    pub fn new(points: Vec<Pos2>) -> Self {
+impl<'a> Polygon<'a> {
+    pub fn new(points: impl Into<PlotPoints<'a>>) -> Self {
         Self {
-            points,
+            points: points.into(),
             fill: Color32::WHITE,
             stroke: Stroke::none(),
+            hatch: None,
+            id: None,
         }
     }
 
+    /// Report the polygon in `PlotResponse::hovered_item` while the pointer is inside it.
+    pub fn id(mut self, id: impl std::hash::Hash) -> Self {
+        self.id = Some(Id::new(id));
+        self
+    }
+
     pub fn fill_color(mut self, color: Color32) -> Self {
         self.fill = color;
         self
@@ -88,21 +720,49 @@ impl Polygon {
         self.stroke = stroke;
         self
     }
+
+    /// Draw a hatch pattern on top of the fill. Set the fill color to transparent to only show
+    /// the hatching. Unlike the fill, the hatching is also correct for non-convex shapes.
+    pub fn hatch(mut self, hatch: HatchStyle) -> Self {
+        self.hatch = Some(hatch);
+        self
+    }
 }
 
-impl PlotItem for Polygon {
-    fn paint(self, painter: &mut Painter, transform: &dyn Fn(&Pos2) -> Pos2) {
+impl PlotItem for Polygon<'_> {
+    fn points(&self) -> &[Value] {
+        &self.points
+    }
+
+    fn hit_test(&self, pointer: Pos2, transform: &ScreenTransform) -> Option<Id> {
+        let points_tf: Vec<Pos2> = self.points.iter().map(|p| transform.position(p)).collect();
+        self.id.filter(|_| polygon_contains(&points_tf, pointer))
+    }
+
+    fn paint(&self, painter: &mut PlotPainter, transform: &ScreenTransform) {
         let Self {
             points,
             fill,
             stroke,
+            hatch,
+            id: _,
         } = self;
 
-        painter.add(Shape::polygon(
-            points.iter().map(|p| transform(p)).collect(),
-            fill,
-            stroke,
-        ));
+        let points_tf: Vec<Pos2> = points.iter().map(|p| transform.position(p)).collect();
+
+        if let Some(hatch) = hatch {
+            painter.add(Shape::polygon(points_tf.clone(), *fill, Stroke::none()));
+            let (angles, spacing, hatch_stroke) = hatch.lines();
+            let clip = painter.clip_rect();
+            angles.iter().for_each(|angle| {
+                hatch_segments(&points_tf, *angle, spacing, clip)
+                    .into_iter()
+                    .for_each(|segment| painter.line_segment(segment, hatch_stroke));
+            });
+            painter.add(Shape::closed_line(points_tf, *stroke));
+        } else {
+            painter.add(Shape::polygon(points_tf, *fill, *stroke));
+        }
     }
 }
 
@@ -116,213 +776,1005 @@ pub enum MarkerShape {
     Star,
 }
 
+impl MarkerShape {
+    /// Draw a single marker of this shape centered at a position in screen coordinates.
+    fn paint(
+        self,
+        painter: &mut PlotPainter,
+        p_tf: Pos2,
+        size: f32,
+        fill: Color32,
+        stroke: Stroke,
+    ) {
+        match self {
+            MarkerShape::Circle => painter.circle(p_tf, size, fill, stroke),
+            MarkerShape::Square => painter.rect(
+                Rect::from_center_size(p_tf, Vec2::new(2. * size, 2. * size)),
+                0.,
+                fill,
+                stroke,
+            ),
+            MarkerShape::Triangle => {
+                let outer_radius = 1.0 * size;
+                let inner_radius = 0.5 * size;
+                let bottom = Vec2::new(0., -outer_radius);
+                let left = Vec2::new(-(3f32.sqrt()) / 2. * outer_radius, inner_radius);
+                let right = Vec2::new(3f32.sqrt() / 2. * outer_radius, inner_radius);
+                let points = vec![p_tf + bottom, p_tf + right, p_tf + left];
+                painter.add(Shape::polygon(points, fill, stroke));
+            }
+            MarkerShape::Plus => {
+                let dx = Vec2::new(size, 0.);
+                painter.line_segment([p_tf - dx, p_tf + dx], stroke);
+                let dy = Vec2::new(0., size);
+                painter.line_segment([p_tf - dy, p_tf + dy], stroke);
+            }
+            MarkerShape::X => {
+                let diag = Vec2::new(size, size) / std::f32::consts::SQRT_2;
+                painter.line_segment([p_tf - diag, p_tf + diag], stroke);
+                let diag = diag.rot90();
+                painter.line_segment([p_tf - diag, p_tf + diag], stroke);
+            }
+            MarkerShape::Star => {
+                let spikes = 8; // Has to be be even.
+                use std::f32::consts::TAU;
+                (0..spikes / 2).for_each(|i| {
+                    let angle = i as f32 / spikes as f32 * TAU;
+                    let diag = Vec2::angled(angle) * size;
+                    painter.line_segment([p_tf - diag, p_tf + diag], stroke);
+                });
+            }
+        };
+    }
+}
+
 pub enum YReference {
-    Constant(f32),
-    Series(Vec<f32>),
+    Constant(f64),
+    Series(Vec<f64>),
+}
+
+pub enum XReference {
+    Constant(f64),
+    Series(Vec<f64>),
 }
 
 /// Plot a set of points.
-pub struct Scatter {
-    points: Vec<Pos2>,
+pub struct Scatter<'a> {
+    points: PlotPoints<'a>,
     fill: Color32,
     stroke: Stroke,
     size: f32,
     shape: MarkerShape,
     stems: Option<(YReference, Stroke)>,
+    x_stems: Option<(XReference, Stroke)>,
+    name: Option<String>,
+    id: Option<Id>,
+    drag_axes: Option<DragAxes>,
+    lod: bool,
 }
 
-impl Scatter {
-    pub fn new(points: Vec<Pos2>) -> Self {
+impl<'a> Scatter<'a> {
+    pub fn new(points: impl Into<PlotPoints<'a>>) -> Self {
         Self {
-            points,
+            points: points.into(),
             fill: Color32::WHITE,
             stroke: Stroke::none(),
             size: 1.,
             shape: MarkerShape::Circle,
             stems: None,
+            x_stems: None,
+            name: None,
+            id: None,
+            drag_axes: None,
+            lod: true,
         }
     }
 
-    pub fn size(mut self, size: f32) -> Self {
-        self.size = size;
+    /// Points at separate x and y values, which need to have the same length.
+    pub fn from_xy<X, Y>(xs: &[X], ys: &[Y]) -> Result<Self, ItemError>
+    where
+        X: Copy + Into<f64>,
+        Y: Copy + Into<f64>,
+    {
+        Ok(Self::new(PlotPoints::from_xy(xs, ys)?))
+    }
+
+    /// Points at the y values with x = 0, 1, 2, …
+    pub fn from_ys<Y: Copy + Into<f64>>(ys: &[Y]) -> Self {
+        Self::new(PlotPoints::from_ys(ys))
+    }
+
+    /// Uniformly sampled y values, the first one at `x0` and the following ones `dx` apart.
+    pub fn from_ys_dx<Y: Copy + Into<f64>>(ys: &[Y], x0: f64, dx: f64) -> Self {
+        Self::new(PlotPoints::from_ys_dx(ys, x0, dx))
+    }
+
+    /// When the points are on average less than a pixel apart on screen, draw a dot for each
+    /// pixel that contains points instead of a marker per point. The more points fall into a
+    /// pixel, the more opaque its dot. Picking and hovering still use every point. On by default.
+    pub fn lod(mut self, on: bool) -> Self {
+        self.lod = on;
         self
     }
 
-    pub fn stems(mut self, reference: YReference, stroke: Stroke) -> Self {
-        if let YReference::Series(series) = &reference {
-            assert!(
-                series.len() == self.points.len(),
-                "The numer of y-axis reference values needs to match the data!"
-            );
-        }
-        self.stems = Some((reference, stroke));
+    /// Name of the points, shown in the legend.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
         self
     }
 
-    pub fn fill_color(mut self, color: Color32) -> Self {
-        self.fill = color;
+    /// Let the points be selected by clicking them. The `id` identifies the points in
+    /// `PlotResponse::selected_points` and has to stay the same between frames.
+    pub fn interactable(mut self, id: impl std::hash::Hash) -> Self {
+        self.id = Some(Id::new(id));
         self
     }
 
-    pub fn stroke(mut self, stroke: Stroke) -> Self {
-        self.stroke = stroke;
+    /// Let the points be moved by dragging them, which requires `interactable`. The plot does
+    /// not pan while a point is grabbed.
+    pub fn draggable(mut self, on: bool) -> Self {
+        self.drag_axes = if on { Some(DragAxes::Both) } else { None };
         self
     }
 
-    pub fn shape(mut self, shape: MarkerShape) -> Self {
-        self.shape = shape;
+    /// Restrict dragging the points to one coordinate.
+    pub fn drag_axes(mut self, axes: DragAxes) -> Self {
+        self.drag_axes = Some(axes);
         self
     }
-}
 
-impl PlotItem for Scatter {
-    fn paint(self, painter: &mut Painter, transform: &dyn Fn(&Pos2) -> Pos2) {
-        let Self {
+    pub fn size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Draw vertical stems from the points to a y-axis reference. A `YReference::Series` needs a
+    /// value for each point.
+    pub fn stems(mut self, reference: YReference, stroke: Stroke) -> Result<Self, ItemError> {
+        if let YReference::Series(series) = &reference {
+            if series.len() != self.points.len() {
+                return Err(ItemError::LengthMismatch {
+                    expected: self.points.len(),
+                    found: series.len(),
+                });
+            }
+        }
+        self.stems = Some((reference, stroke));
+        Ok(self)
+    }
+
+    /// Draw horizontal stems from the points to an x-axis reference. Can be combined with `stems`.
+    pub fn x_stems(mut self, reference: XReference, stroke: Stroke) -> Result<Self, ItemError> {
+        if let XReference::Series(series) = &reference {
+            if series.len() != self.points.len() {
+                return Err(ItemError::LengthMismatch {
+                    expected: self.points.len(),
+                    found: series.len(),
+                });
+            }
+        }
+        self.x_stems = Some((reference, stroke));
+        Ok(self)
+    }
+
+    pub fn fill_color(mut self, color: Color32) -> Self {
+        self.fill = color;
+        self
+    }
+
+    pub fn stroke(mut self, stroke: Stroke) -> Self {
+        self.stroke = stroke;
+        self
+    }
+
+    pub fn shape(mut self, shape: MarkerShape) -> Self {
+        self.shape = shape;
+        self
+    }
+}
+
+impl PlotItem for Scatter<'_> {
+    fn points(&self) -> &[Value] {
+        &self.points
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    fn color(&self) -> Color32 {
+        if self.fill == Color32::TRANSPARENT {
+            self.stroke.color
+        } else {
+            self.fill
+        }
+    }
+
+    fn highlight(&mut self) {
+        self.size *= 1.5;
+    }
+
+    fn id(&self) -> Option<Id> {
+        self.id
+    }
+
+    fn pick_radius(&self) -> f32 {
+        self.size.max(3.) + 2.
+    }
+
+    fn drag_axes(&self) -> Option<DragAxes> {
+        self.drag_axes
+    }
+
+    fn hit_test(&self, pointer: Pos2, transform: &ScreenTransform) -> Option<Id> {
+        let radius = self.pick_radius();
+        self.id.filter(|_| {
+            self.points
+                .iter()
+                .any(|p| transform.position(p).distance(pointer) <= radius)
+        })
+    }
+
+    fn paint(&self, painter: &mut PlotPainter, transform: &ScreenTransform) {
+        let Self {
             points,
             fill,
             stroke,
             size,
             shape,
             stems,
+            x_stems,
+            name: _,
+            id: _,
+            drag_axes: _,
+            lod,
         } = self;
 
-        points.iter().enumerate().for_each(|(i, p)| {
-            let p_tf = transform(p);
+        // Markers out of view are skipped, their stems might still be visible.
+        let visible = painter.clip_rect().expand(size + stroke.width);
+        let mut points_tf = painter.scratch();
+        transform.positions_into(points, &mut points_tf);
+        // Less than a pixel of the visible area per point, see `Scatter::lod`.
+        let dense = *lod && {
+            let count = points_tf.iter().filter(|p| visible.contains(**p)).count();
+            count > 0 && visible.area() < count as f32
+        };
+        if dense {
+            let clip = painter.clip_rect();
+            painter.add(Shape::mesh(density_dots(&points_tf, clip, self.color())));
+        }
+        points
+            .iter()
+            .zip(&points_tf)
+            .enumerate()
+            .for_each(|(i, (p, p_tf))| {
+                if !p.is_finite() {
+                    return;
+                }
+                let p_tf = *p_tf;
 
-            if let Some((reference, stroke)) = &stems {
-                let current_ref = match reference {
-                    YReference::Constant(c) => *c,
-                    YReference::Series(s) => *s.get(i).unwrap(),
-                };
+                if let Some((reference, stroke)) = &stems {
+                    let current_ref = match reference {
+                        YReference::Constant(c) => *c,
+                        YReference::Series(s) => *s.get(i).unwrap(),
+                    };
 
-                let p_ref_tf = transform(&Pos2::new(p.x, current_ref));
+                    if current_ref.is_finite() {
+                        let p_ref_tf = transform.position(&Value::new(p.x, current_ref));
+                        painter.line_segment([p_ref_tf, p_tf], *stroke);
+                    }
+                }
 
-                painter.line_segment([p_ref_tf, p_tf], *stroke);
-            }
+                if let Some((reference, stroke)) = &x_stems {
+                    let current_ref = match reference {
+                        XReference::Constant(c) => *c,
+                        XReference::Series(s) => *s.get(i).unwrap(),
+                    };
 
-            match shape {
-                MarkerShape::Circle => painter.circle(p_tf, size, fill, stroke),
-                MarkerShape::Square => painter.rect(
-                    Rect::from_center_size(p_tf, Vec2::new(2. * size, 2. * size)),
-                    0.,
-                    fill,
-                    stroke,
-                ),
-                MarkerShape::Triangle => {
-                    let outer_radius = 1.0 * size;
-                    let inner_radius = 0.5 * size;
-                    let bottom = Vec2::new(0., -outer_radius);
-                    let left = Vec2::new(-(3f32.sqrt()) / 2. * outer_radius, inner_radius);
-                    let right = Vec2::new(3f32.sqrt() / 2. * outer_radius, inner_radius);
-                    let points = vec![p_tf + bottom, p_tf + right, p_tf + left];
-                    painter.add(Shape::polygon(points, fill, stroke));
+                    if current_ref.is_finite() {
+                        let p_ref_tf = transform.position(&Value::new(current_ref, p.y));
+                        painter.line_segment([p_ref_tf, p_tf], *stroke);
+                    }
                 }
-                MarkerShape::Plus => {
-                    let dx = Vec2::new(size, 0.);
-                    painter.line_segment([p_tf - dx, p_tf + dx], stroke);
-                    let dy = Vec2::new(0., size);
-                    painter.line_segment([p_tf - dy, p_tf + dy], stroke);
-                }
-                MarkerShape::X => {
-                    let diag = Vec2::new(size, size) / std::f32::consts::SQRT_2;
-                    painter.line_segment([p_tf - diag, p_tf + diag], stroke);
-                    let diag = diag.rot90();
-                    painter.line_segment([p_tf - diag, p_tf + diag], stroke);
-                }
-                MarkerShape::Star => {
-                    let spikes = 8; // Has to be be even.
-                    use std::f32::consts::TAU;
-                    (0..spikes / 2).for_each(|i| {
-                        let angle = i as f32 / spikes as f32 * TAU;
-                        let diag = Vec2::angled(angle) * size;
-                        painter.line_segment([p_tf - diag, p_tf + diag], stroke);
-                    });
+
+                if !dense && visible.contains(p_tf) {
+                    shape.paint(painter, p_tf, *size, *fill, *stroke);
                 }
-            };
-        });
+            });
+        painter.recycle(points_tf);
     }
 }
 
+/// A dot for each pixel of `clip` that contains points, in screen coordinates. Its opacity
+/// grows with the number of points in it.
+fn density_dots(points: &[Pos2], clip: Rect, color: Color32) -> Mesh {
+    let mut counts: HashMap<(i32, i32), u32> = HashMap::new();
+    points.iter().filter(|p| clip.contains(**p)).for_each(|p| {
+        *counts
+            .entry((p.x.floor() as i32, p.y.floor() as i32))
+            .or_default() += 1
+    });
+    let max = counts.values().copied().max().unwrap_or(1) as f32;
+    let mut mesh = Mesh::default();
+    counts.into_iter().for_each(|((x, y), count)| {
+        // Single points have to stay visible next to dense clusters.
+        let alpha = ((count as f32).ln_1p() / max.ln_1p()).max(0.3);
+        let rect = Rect::from_min_size(pos2(x as f32, y as f32), vec2(1., 1.));
+        mesh.add_colored_rect(rect, color.linear_multiply(alpha));
+    });
+    mesh
+}
+
+/// How the segments of a `Line` are joined.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JoinStyle {
+    /// Leave it to egui, which is fast but leaves notches at sharp corners of thick lines.
+    Tessellated,
+    /// Extend the edges until they meet. Corners too sharp for `MITER_LIMIT` are beveled
+    /// instead of ending in a spike.
+    Miter,
+    /// Cut the corners off.
+    Bevel,
+    Round,
+}
+
+/// The longest a miter may reach beyond the corner, relative to half the width of the line.
+const MITER_LIMIT: f32 = 4.;
+
 /// Plot a sequence of connected points.
-pub struct Line {
-    points: Vec<Pos2>,
+pub struct Line<'a> {
+    points: PlotPoints<'a>,
     color: Color32,
     weight: f32,
     area_fill: Option<(YReference, Color32)>,
+    skip_nan: bool,
+    threshold: Option<(YReference, Color32, Color32)>,
+    name: Option<String>,
+    id: Option<Id>,
+    downsample: bool,
+    simplify: Option<f32>,
+    x_sorted: bool,
+    join_style: JoinStyle,
+    /// The visible x range, see `PlotItem::cull`.
+    visible_x: Option<RangeInclusive<f64>>,
 }
 
-impl Line {
-    pub fn new(points: Vec<Pos2>) -> Self {
+impl<'a> Line<'a> {
+    pub fn new(points: impl Into<PlotPoints<'a>>) -> Self {
         Self {
-            points,
+            points: points.into(),
             color: Color32::WHITE,
             weight: 1.,
             area_fill: None,
+            skip_nan: false,
+            threshold: None,
+            name: None,
+            id: None,
+            downsample: true,
+            simplify: None,
+            x_sorted: false,
+            join_style: JoinStyle::Tessellated,
+            visible_x: None,
         }
     }
 
+    /// A line through separate x and y values, which need to have the same length.
+    pub fn from_xy<X, Y>(xs: &[X], ys: &[Y]) -> Result<Self, ItemError>
+    where
+        X: Copy + Into<f64>,
+        Y: Copy + Into<f64>,
+    {
+        Ok(Self::new(PlotPoints::from_xy(xs, ys)?))
+    }
+
+    /// A line through the y values at x = 0, 1, 2, …
+    pub fn from_ys<Y: Copy + Into<f64>>(ys: &[Y]) -> Self {
+        Self::from_ys_dx(ys, 0., 1.)
+    }
+
+    /// A uniformly sampled signal, the first sample at `x0` and the following ones `dx` apart.
+    /// The x values are known to be sorted for a positive `dx`, see `x_sorted`.
+    pub fn from_ys_dx<Y: Copy + Into<f64>>(ys: &[Y], x0: f64, dx: f64) -> Self {
+        let mut line = Self::new(PlotPoints::from_ys_dx(ys, x0, dx));
+        line.x_sorted = x0.is_finite() && dx >= 0. && dx.is_finite();
+        line
+    }
+
+    /// Lines with many more points than pixels are drawn with only the extreme points of each
+    /// pixel column, which looks the same but is much faster. On by default.
+    pub fn downsample(mut self, on: bool) -> Self {
+        self.downsample = on;
+        self
+    }
+
+    /// Leave out the points that deviate less than `tolerance` pixels from the simplified line,
+    /// using the Ramer–Douglas–Peucker algorithm. The remaining points are not moved, so corners
+    /// stay exact. Not applied if the area fill follows a `YReference::Series`.
+    pub fn simplify(mut self, tolerance: f32) -> Self {
+        self.simplify = Some(tolerance);
+        self
+    }
+
+    /// Promise that the x values never decrease, e.g. for recordings over time. The visible part
+    /// and the points near the pointer are then found by binary search, without checking the
+    /// order of all points every frame. Debug builds assert that the points are sorted.
+    pub fn x_sorted(mut self, sorted: bool) -> Self {
+        debug_assert!(
+            !sorted || is_sorted_by_x(&self.points),
+            "Line::x_sorted: the x values are not sorted"
+        );
+        self.x_sorted = sorted;
+        self
+    }
+
+    /// Report the line in `PlotResponse::hovered_item` while the pointer is within a few pixels
+    /// of it.
+    pub fn id(mut self, id: impl std::hash::Hash) -> Self {
+        self.id = Some(Id::new(id));
+        self
+    }
+
     pub fn color(mut self, color: Color32) -> Self {
         self.color = color;
         self
     }
 
+    /// Name of the line, shown in the legend.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
     pub fn weight(mut self, weight: f32) -> Self {
         self.weight = weight;
         self
     }
 
-    pub fn area_fill(mut self, reference: YReference, color: Color32) -> Self {
+    /// How the segments are joined. Except for the default `JoinStyle::Tessellated`, the line is
+    /// built as a single mesh with round caps, which looks better for thick lines.
+    pub fn join_style(mut self, style: JoinStyle) -> Self {
+        self.join_style = style;
+        self
+    }
+
+    /// Fill the area between the line and a reference. A series reference needs a value for
+    /// every point.
+    pub fn area_fill(mut self, reference: YReference, color: Color32) -> Result<Self, ItemError> {
+        if let YReference::Series(series) = &reference {
+            if series.len() != self.points.len() {
+                return Err(ItemError::LengthMismatch {
+                    expected: self.points.len(),
+                    found: series.len(),
+                });
+            }
+        }
         self.area_fill = Some((reference, color));
+        Ok(self)
+    }
+
+    /// By default, non-finite points split the line so gaps in the data show as gaps in the plot.
+    /// If enabled, such points are skipped instead and their neighbors are connected directly.
+    pub fn skip_nan(mut self, on: bool) -> Self {
+        self.skip_nan = on;
         self
     }
+
+    /// Use different colors for the parts of the line above and below a reference.
+    /// The color switches exactly where the line crosses the reference.
+    pub fn threshold_colors(
+        mut self,
+        reference: YReference,
+        above: Color32,
+        below: Color32,
+    ) -> Result<Self, ItemError> {
+        if let YReference::Series(series) = &reference {
+            if series.len() != self.points.len() {
+                return Err(ItemError::LengthMismatch {
+                    expected: self.points.len(),
+                    found: series.len(),
+                });
+            }
+        }
+        self.threshold = Some((reference, above, below));
+        Ok(self)
+    }
+
+    /// Split a run of points into pieces that lie entirely above or below the reference.
+    /// The crossing points are found by linear interpolation in plot coordinates.
+    fn split_at_threshold(
+        points: &[Value],
+        run: &[usize],
+        reference: &YReference,
+        above: Color32,
+        below: Color32,
+    ) -> Vec<(Vec<Value>, Color32)> {
+        let distance = |i: usize| match reference {
+            YReference::Constant(c) => points[i].y - c,
+            YReference::Series(s) => points[i].y - s[i],
+        };
+        let color = |d: f64| if d >= 0. { above } else { below };
+
+        let mut pieces = Vec::new();
+        let mut current = vec![points[run[0]]];
+        let mut current_color = color(distance(run[0]));
+        run.windows(2).for_each(|w| {
+            let (d0, d1) = (distance(w[0]), distance(w[1]));
+            if color(d1) != current_color && d0 != d1 {
+                let t = d0 / (d0 - d1);
+                let crossing = points[w[0]].lerp(points[w[1]], t);
+                current.push(crossing);
+                pieces.push((
+                    std::mem::replace(&mut current, vec![crossing]),
+                    current_color,
+                ));
+                current_color = color(d1);
+            }
+            current.push(points[w[1]]);
+        });
+        pieces.push((current, current_color));
+        pieces
+    }
+}
+
+/// Reduce consecutive points in the same pixel column to the first, lowest, highest and last one,
+/// which keeps the envelope of the line. Points outside `clip` are merged into one column on
+/// either side. The columns of `sorted` points are delimited by binary search.
+fn downsample(points: &[Pos2], clip: Rect, sorted: bool) -> Vec<Pos2> {
+    let column = |p: &Pos2| p.x.max(clip.left() - 1.).min(clip.right() + 1.).floor() as i64;
+    let mut reduced = Vec::new();
+    let mut start = 0;
+    while start < points.len() {
+        let current = column(&points[start]);
+        let len = if sorted {
+            points[start..].partition_point(|p| column(p) == current)
+        } else {
+            points[start..]
+                .iter()
+                .position(|p| column(p) != current)
+                .unwrap_or(points.len() - start)
+        };
+        let bucket = &points[start..start + len];
+        let extreme = |better: fn(f32, f32) -> bool| {
+            (0..bucket.len()).fold(0, |best, i| {
+                if better(bucket[i].y, bucket[best].y) {
+                    i
+                } else {
+                    best
+                }
+            })
+        };
+        let mut indices = vec![0, extreme(|a, b| a < b), extreme(|a, b| a > b), len - 1];
+        indices.sort_unstable();
+        indices.dedup();
+        reduced.extend(indices.into_iter().map(|i| bucket[i]));
+        start += len;
+    }
+    reduced
+}
+
+/// Ramer–Douglas–Peucker: keep only the points that are more than `tolerance` away from the
+/// segment between the kept points on either side of them.
+fn simplify(points: &[Pos2], tolerance: f32) -> Vec<Pos2> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+    let mut stack = vec![(0, points.len() - 1)];
+    while let Some((first, last)) = stack.pop() {
+        let farthest = (first + 1..last)
+            .map(|i| (i, segment_distance(points[i], points[first], points[last])))
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        if let Some((i, distance)) = farthest {
+            if distance > tolerance {
+                keep[i] = true;
+                stack.push((first, i));
+                stack.push((i, last));
+            }
+        }
+    }
+    points
+        .iter()
+        .zip(keep)
+        .filter(|(_, keep)| *keep)
+        .map(|(p, _)| *p)
+        .collect()
+}
+
+/// Add a line through `points`, given in screen coordinates, with the segments joined by
+/// `join_style`.
+fn add_line(painter: &mut PlotPainter, points: Vec<Pos2>, stroke: Stroke, join_style: JoinStyle) {
+    let feathering = painter.feathering();
+    // Lines thinner than the fringe have no visible corners.
+    if join_style == JoinStyle::Tessellated || stroke.width <= feathering {
+        painter.add(Shape::line(points, stroke));
+    } else {
+        painter.add(Shape::mesh(line_mesh(
+            &points, stroke, join_style, feathering,
+        )));
+        painter.recycle(points);
+    }
+}
+
+/// Extrude a polyline into a triangle strip with round caps. Like egui's tessellator, the edges
+/// fade out over `feathering` so the line looks the same as the ones egui draws.
+fn line_mesh(points: &[Pos2], stroke: Stroke, join_style: JoinStyle, feathering: f32) -> Mesh {
+    let mut points: Vec<Pos2> = points.iter().copied().filter(|p| p.is_finite()).collect();
+    points.dedup();
+    if points.len() < 2 {
+        return Mesh::default();
+    }
+    chunked_mesh(points.len(), |corners| {
+        line_strip(&points, corners, stroke, join_style, feathering)
+    })
 }
 
-impl PlotItem for Line {
-    fn paint(self, painter: &mut Painter, transform: &dyn Fn(&Pos2) -> Pos2) {
+/// The part of a line mesh with the sections at the points in `corners`. It runs on to the first
+/// section at the next point, where the next part starts, and gets a cap at either end of the
+/// whole line.
+fn line_strip(
+    points: &[Pos2],
+    corners: Range<usize>,
+    stroke: Stroke,
+    join_style: JoinStyle,
+    feathering: f32,
+) -> Mesh {
+    let mut mesh = Mesh::default();
+    let inner = (stroke.width - feathering) / 2.;
+    let outer = (stroke.width + feathering) / 2.;
+    let color = stroke.color;
+    // A section across the line at `center`, connected to the previous one.
+    let mut connected = false;
+    let mut section = |mesh: &mut Mesh, center: Pos2, normal: Vec2| {
+        let idx = mesh.vertices.len() as u32;
+        mesh.colored_vertex(center + outer * normal, Color32::TRANSPARENT);
+        mesh.colored_vertex(center + inner * normal, color);
+        mesh.colored_vertex(center - inner * normal, color);
+        mesh.colored_vertex(center - outer * normal, Color32::TRANSPARENT);
+        if connected {
+            (0..3).for_each(|k| {
+                mesh.add_triangle(idx - 4 + k, idx - 4 + k + 1, idx + k);
+                mesh.add_triangle(idx - 4 + k + 1, idx + k, idx + k + 1);
+            });
+        }
+        connected = true;
+    };
+
+    let last = points.len() - 1;
+    if corners.start == 0 {
+        let normal = segment_normal(points[0], points[1]);
+        round_cap(&mut mesh, points[0], -normal, inner, outer, color);
+    }
+    let next = corners.end;
+    corners.for_each(|i| {
+        corner_normals(points, i, join_style, |normal| {
+            section(&mut mesh, points[i], normal)
+        })
+    });
+    if next <= last {
+        let mut first = None;
+        corner_normals(points, next, join_style, |normal| {
+            first.get_or_insert(normal);
+        });
+        if let Some(normal) = first {
+            section(&mut mesh, points[next], normal);
+        }
+    } else {
+        let normal = segment_normal(points[last - 1], points[last]);
+        round_cap(&mut mesh, points[last], normal, inner, outer, color);
+    }
+    mesh
+}
+
+fn segment_normal(a: Pos2, b: Pos2) -> Vec2 {
+    (b - a).normalized().rot90()
+}
+
+/// Pass the normals of the sections at point `i` of a line to `add`, in order. Corners joined
+/// by a bevel or rounded have more than one.
+fn corner_normals(points: &[Pos2], i: usize, join_style: JoinStyle, mut add: impl FnMut(Vec2)) {
+    if i == 0 {
+        return add(segment_normal(points[0], points[1]));
+    }
+    if i == points.len() - 1 {
+        return add(segment_normal(points[i - 1], points[i]));
+    }
+    let n0 = segment_normal(points[i - 1], points[i]);
+    let n1 = segment_normal(points[i], points[i + 1]);
+    let miter = (n0 + n1).normalized();
+    let miter_scale = 1. / dot(miter, n0);
+    match join_style {
+        _ if dot(n0, n1) > 0.9999 => add(n0),
+        JoinStyle::Miter if miter_scale.is_finite() && miter_scale <= MITER_LIMIT => {
+            add(miter_scale * miter)
+        }
+        JoinStyle::Round => {
+            // Sweep the section around the corner, the outer side traces an arc.
+            let angle = n0.y.atan2(n0.x);
+            let mut turn = n1.y.atan2(n1.x) - angle;
+            if turn > std::f32::consts::PI {
+                turn -= std::f32::consts::TAU;
+            } else if turn < -std::f32::consts::PI {
+                turn += std::f32::consts::TAU;
+            }
+            let steps = (turn.abs() / std::f32::consts::FRAC_PI_8).ceil().max(1.) as usize;
+            (0..=steps)
+                .for_each(|step| add(Vec2::angled(angle + turn * step as f32 / steps as f32)));
+        }
+        _ => {
+            add(n0);
+            add(n1);
+        }
+    }
+}
+
+/// Build the mesh for the indices `0..len` with `build`, which makes the part for a range of
+/// them. Long inputs are split up and built in parallel, the parts are joined in order.
+fn chunked_mesh(len: usize, build: impl Fn(Range<usize>) -> Mesh + Sync + Send) -> Mesh {
+    #[cfg(feature = "rayon")]
+    if len >= super::plot::PARALLEL_MIN_POINTS {
+        use rayon::prelude::*;
+        const CHUNK: usize = 16_384;
+        let starts: Vec<usize> = (0..len).step_by(CHUNK).collect();
+        let parts: Vec<Mesh> = starts
+            .par_iter()
+            .map(|start| build(*start..(start + CHUNK).min(len)))
+            .collect();
+        // Not `Mesh::append`, which would throw away the reserved space of an empty mesh.
+        let mut mesh = Mesh::default();
+        mesh.vertices
+            .reserve(parts.iter().map(|part| part.vertices.len()).sum());
+        mesh.indices
+            .reserve(parts.iter().map(|part| part.indices.len()).sum());
+        parts.into_iter().for_each(|part| {
+            let offset = mesh.vertices.len() as u32;
+            mesh.indices
+                .extend(part.indices.iter().map(|idx| idx + offset));
+            mesh.vertices.extend(part.vertices);
+        });
+        return mesh;
+    }
+    build(0..len)
+}
+
+/// Add a half disk at an end of a line, sweeping from `normal` to `-normal` by increasing angle.
+fn round_cap(mesh: &mut Mesh, center: Pos2, normal: Vec2, inner: f32, outer: f32, color: Color32) {
+    let start = normal.y.atan2(normal.x);
+    let steps = (outer.ceil() as usize).clamp(4, 32);
+    let idx = mesh.vertices.len() as u32;
+    mesh.colored_vertex(center, color);
+    (0..=steps).for_each(|step| {
+        let direction = Vec2::angled(start + std::f32::consts::PI * step as f32 / steps as f32);
+        mesh.colored_vertex(center + inner * direction, color);
+        mesh.colored_vertex(center + outer * direction, Color32::TRANSPARENT);
+    });
+    (0..steps as u32).for_each(|step| {
+        let (i, o) = (idx + 1 + 2 * step, idx + 2 + 2 * step);
+        mesh.add_triangle(idx, i, i + 2);
+        mesh.add_triangle(i, o, i + 2);
+        mesh.add_triangle(o, o + 2, i + 2);
+    });
+}
+
+/// The indices of the points within `x_range`, and one more on either side so the line still
+/// leaves the view. Sorted points are searched, otherwise everything between the first and the
+/// last visible point is kept.
+fn visible_indices(points: &[Value], sorted: bool, x_range: &RangeInclusive<f64>) -> Range<usize> {
+    let visible = |p: &Value| x_range.contains(&p.x);
+    let (start, end) = if sorted {
+        (
+            points.partition_point(|p| p.x < *x_range.start()),
+            points.partition_point(|p| p.x <= *x_range.end()),
+        )
+    } else {
+        match points.iter().position(visible) {
+            Some(first) => (first, points.iter().rposition(visible).unwrap() + 1),
+            // The line might still cross the view.
+            None => return 0..points.len(),
+        }
+    };
+    start.saturating_sub(1)..(end + 1).min(points.len())
+}
+
+/// Split the indices of the finite points into runs of connected points.
+fn finite_runs(points: &[Value], indices: Range<usize>, skip_nan: bool) -> Vec<Vec<usize>> {
+    let mut runs = vec![Vec::new()];
+    indices.for_each(|i| {
+        if points[i].is_finite() {
+            runs.last_mut().unwrap().push(i);
+        } else if !skip_nan && !runs.last().unwrap().is_empty() {
+            runs.push(Vec::new());
+        }
+    });
+    runs.retain(|run| !run.is_empty());
+    runs
+}
+
+/// Replace the contents of `out` with the screen positions of the points at `indices`, in the
+/// same order. See `ScreenTransform::positions`.
+fn positions_at(
+    points: &[Value],
+    indices: &[usize],
+    transform: &ScreenTransform,
+    out: &mut Vec<Pos2>,
+) {
+    #[cfg(feature = "rayon")]
+    if indices.len() >= super::plot::PARALLEL_MIN_POINTS {
+        use rayon::prelude::*;
+        return indices
+            .par_iter()
+            .map(|i| transform.position(&points[*i]))
+            .collect_into_vec(out);
+    }
+    out.clear();
+    out.extend(indices.iter().map(|i| transform.position(&points[*i])));
+}
+
+impl PlotItem for Line<'_> {
+    fn points(&self) -> &[Value] {
+        &self.points
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    fn color(&self) -> Color32 {
+        self.color
+    }
+
+    fn highlight(&mut self) {
+        self.weight *= 2.;
+    }
+
+    fn sorted_by_x(&self) -> bool {
+        self.x_sorted || is_sorted_by_x(&self.points)
+    }
+
+    fn cull(&mut self, bounds: PlotBounds) {
+        self.visible_x = Some(bounds.x_range());
+    }
+
+    fn hit_test(&self, pointer: Pos2, transform: &ScreenTransform) -> Option<Id> {
+        let id = self.id?;
+        let max_distance = self.weight / 2. + LINE_HIT_DISTANCE;
+        // Only the segments around the pointer can be close enough.
+        let indices = if self.sorted_by_x() {
+            let left = transform.value(&(pointer - max_distance * Vec2::X)).x;
+            let right = transform.value(&(pointer + max_distance * Vec2::X)).x;
+            visible_indices(&self.points, true, &(left.min(right)..=left.max(right)))
+        } else {
+            0..self.points.len()
+        };
+        let hit = finite_runs(&self.points, indices, self.skip_nan)
+            .iter()
+            .any(|run| {
+                run.windows(2).any(|w| {
+                    let (a, b) = (
+                        transform.position(&self.points[w[0]]),
+                        transform.position(&self.points[w[1]]),
+                    );
+                    segment_distance(pointer, a, b) <= max_distance
+                })
+            });
+        Some(id).filter(|_| hit)
+    }
+
+    fn paint(&self, painter: &mut PlotPainter, transform: &ScreenTransform) {
         let Self {
             points,
             color,
             weight,
             area_fill,
+            skip_nan,
+            threshold,
+            name: _,
+            id: _,
+            downsample: downsample_on,
+            simplify: tolerance,
+            x_sorted,
+            join_style,
+            visible_x,
         } = self;
 
-        // TODO: Ew. Make this better.
+        let sorted = *x_sorted || is_sorted_by_x(points);
+        let indices = match visible_x {
+            Some(x_range) => visible_indices(points, sorted, x_range),
+            None => 0..points.len(),
+        };
+        let runs = finite_runs(points, indices, *skip_nan);
+        let clip = painter.clip_rect();
+        // The fill follows every sample of a series reference, so the line has to as well.
+        let tolerance =
+            tolerance.filter(|_| !matches!(area_fill, Some((YReference::Series(_), _))));
+
+        // The fill is a single strip between the line and the reference, split where either is
+        // not finite. A non-finite point also separates the runs.
         if let Some((reference, color)) = area_fill {
-            points.windows(2).enumerate().for_each(|(i, w)| {
-                let y_ref = match &reference {
-                    YReference::Constant(c) => (*c, *c),
-                    YReference::Series(s) => (s[i], s[i + 1]),
-                };
-                let start_down = transform(&pos2(w[0].x, y_ref.0));
-                let end_down = transform(&pos2(w[1].x, y_ref.1));
-                painter.add(Shape::polygon(
-                    vec![transform(&w[1]), transform(&w[0]), start_down, end_down],
-                    color,
-                    Stroke::default(),
-                ));
+            let gap = pos2(f32::NAN, f32::NAN);
+            let mut line = painter.scratch();
+            let mut base = painter.scratch();
+            runs.iter().for_each(|run| {
+                run.iter().for_each(|i| {
+                    let y_ref = match &reference {
+                        YReference::Constant(c) => *c,
+                        YReference::Series(s) => s[*i],
+                    };
+                    line.push(transform.position(&points[*i]));
+                    base.push(if y_ref.is_finite() {
+                        transform.position(&Value::new(points[*i].x, y_ref))
+                    } else {
+                        gap
+                    });
+                });
+                line.push(gap);
+                base.push(gap);
             });
+            painter.add(Shape::mesh(band_mesh(&base, &line, *color)));
+            painter.recycle(line);
+            painter.recycle(base);
         }
 
-        let points: Vec<Pos2> = points.iter().map(|p| transform(p)).collect();
-
-        painter.add(Shape::line(points, Stroke::new(weight, color)));
+        runs.iter()
+            .filter(|run| run.len() > 1)
+            .for_each(|run| match &threshold {
+                Some((reference, above, below)) => {
+                    Self::split_at_threshold(points, run, reference, *above, *below)
+                        .into_iter()
+                        .for_each(|(piece, color)| {
+                            let mut piece_tf: Vec<Pos2> =
+                                piece.iter().map(|p| transform.position(p)).collect();
+                            if let Some(tolerance) = tolerance {
+                                piece_tf = simplify(&piece_tf, tolerance);
+                            }
+                            add_line(painter, piece_tf, Stroke::new(*weight, color), *join_style);
+                        });
+                }
+                None => {
+                    let mut points_tf = painter.scratch();
+                    positions_at(points, run, transform, &mut points_tf);
+                    let visible = points_tf
+                        .iter()
+                        .filter(|p| clip.x_range().contains(&p.x))
+                        .count();
+                    if *downsample_on && visible as f32 > 2. * clip.width() {
+                        let reduced = downsample(&points_tf, clip, sorted);
+                        painter.recycle(std::mem::replace(&mut points_tf, reduced));
+                    }
+                    if let Some(tolerance) = tolerance {
+                        let simplified = simplify(&points_tf, tolerance);
+                        painter.recycle(std::mem::replace(&mut points_tf, simplified));
+                    }
+                    add_line(
+                        painter,
+                        points_tf,
+                        Stroke::new(*weight, *color),
+                        *join_style,
+                    );
+                }
+            });
     }
 }
 
-pub struct Quiver {
-    points: Vec<Pos2>,
+pub struct Quiver<'a> {
+    points: PlotPoints<'a>,
     directions: Vec<Vec2>,
     color: Color32,
     weight: f32,
+    magnitude_colors: Option<(Option<RangeInclusive<f32>>, Colormap)>,
+    normalized_length: Option<f32>,
 }
 
-impl Quiver {
-    pub fn new(points: Vec<Pos2>, directions: Vec<Vec2>) -> Self {
+impl<'a> Quiver<'a> {
+    pub fn new(points: impl Into<PlotPoints<'a>>, directions: Vec<Vec2>) -> Self {
         Self {
-            points,
+            points: points.into(),
             directions,
             color: Color32::WHITE,
             weight: 1.,
+            magnitude_colors: None,
+            normalized_length: None,
         }
     }
 
@@ -335,25 +1787,1073 @@ impl Quiver {
         self.weight = weight;
         self
     }
+
+    /// Color each arrow by the length of its direction vector. Without a range, the colormap
+    /// spans the smallest to the largest magnitude.
+    pub fn color_by_magnitude(
+        mut self,
+        range: Option<RangeInclusive<f32>>,
+        colormap: Colormap,
+    ) -> Self {
+        self.magnitude_colors = Some((range, colormap));
+        self
+    }
+
+    /// Draw all arrows with the same length in plot units. Their original magnitude can still be
+    /// shown with `color_by_magnitude`.
+    pub fn normalize(mut self, length: f32) -> Self {
+        self.normalized_length = Some(length);
+        self
+    }
+}
+
+impl Quiver<'_> {
+    /// The arrow for `direction` in plot units.
+    fn arrow(&self, direction: Vec2) -> Vec2 {
+        let magnitude = direction.length();
+        match self.normalized_length {
+            Some(length) if magnitude > 0. => direction * (length / magnitude),
+            _ => direction,
+        }
+    }
 }
 
-impl PlotItem for Quiver {
-    fn paint(self, painter: &mut Painter, transform: &dyn Fn(&Pos2) -> Pos2) {
+impl PlotItem for Quiver<'_> {
+    fn points(&self) -> &[Value] {
+        &self.points
+    }
+
+    fn bounds(&self) -> Option<PlotBounds> {
+        let mut bounds = PlotBounds::NOTHING;
+        self.points
+            .iter()
+            .zip(self.directions.iter())
+            .map(|(point, direction)| {
+                let arrow = self.arrow(*direction);
+                Value::new(point.x + arrow.x as f64, point.y + arrow.y as f64)
+            })
+            .filter(|tip| tip.is_finite())
+            .for_each(|tip| bounds.extend_with(tip));
+        Some(bounds).filter(|bounds| bounds.is_finite())
+    }
+
+    fn paint(&self, painter: &mut PlotPainter, transform: &ScreenTransform) {
         let Self {
             points,
             directions,
             color,
             weight,
+            magnitude_colors,
+            normalized_length: _,
         } = self;
 
+        let magnitude_range = magnitude_colors.as_ref().map(|(range, _)| {
+            range.clone().unwrap_or_else(|| {
+                let magnitudes = directions.iter().map(|d| d.length());
+                let min = magnitudes.clone().fold(f32::INFINITY, f32::min);
+                let max = magnitudes.fold(f32::NEG_INFINITY, f32::max);
+                min..=max
+            })
+        });
+
         points
             .iter()
             .zip(directions.iter())
             .for_each(|(point, direction)| {
-                let p0 = transform(point);
-                let p1 = transform(&(*point + *direction));
+                let magnitude = direction.length();
+                let color = match (&magnitude_colors, &magnitude_range) {
+                    (Some((_, colormap)), Some(range)) if range.end() > range.start() => {
+                        colormap.color(remap(magnitude, range.clone(), 0.0..=1.0))
+                    }
+                    (Some((_, colormap)), _) => colormap.color(0.),
+                    _ => *color,
+                };
+                let direction = self.arrow(*direction);
+
+                let p0 = transform.position(point);
+                let p1 = transform.position(&Value::new(
+                    point.x + direction.x as f64,
+                    point.y + direction.y as f64,
+                ));
+
+                painter.arrow(p0, p1 - p0, Stroke::new(*weight, color));
+            });
+    }
+}
+
+/// Cubic Bézier curves. Each segment is given by its four control points in plot coordinates.
+pub struct Bezier {
+    segments: Vec<[Value; 4]>,
+    stroke: Stroke,
+    show_control_points: bool,
+}
+
+impl Bezier {
+    pub fn new(segments: Vec<[impl IntoPlotPoint; 4]>) -> Self {
+        Self {
+            segments: segments
+                .into_iter()
+                .map(|[p0, p1, p2, p3]| {
+                    [
+                        p0.into_plot_point(),
+                        p1.into_plot_point(),
+                        p2.into_plot_point(),
+                        p3.into_plot_point(),
+                    ]
+                })
+                .collect(),
+            stroke: Stroke::new(1., Color32::WHITE),
+            show_control_points: false,
+        }
+    }
+
+    pub fn stroke(mut self, stroke: Stroke) -> Self {
+        self.stroke = stroke;
+        self
+    }
+
+    /// Also draw the control polygon and the handles. Useful for debugging.
+    pub fn show_control_points(mut self, on: bool) -> Self {
+        self.show_control_points = on;
+        self
+    }
+
+    /// Flatten a segment that is already in screen coordinates. The number of subdivisions is
+    /// chosen such that the deviation from the true curve stays below a fraction of a pixel.
+    fn flatten(segment: &[Pos2; 4]) -> Vec<Pos2> {
+        let [p0, p1, p2, p3] = *segment;
+        let tolerance = 0.2;
+        let dd = (p0.to_vec2() - 2. * p1.to_vec2() + p2.to_vec2())
+            .length()
+            .max((p1.to_vec2() - 2. * p2.to_vec2() + p3.to_vec2()).length());
+        // A NaN distance casts to zero subdivisions.
+        let n = ((0.75 * dd / tolerance).sqrt().ceil().clamp(1., 1000.) as usize).max(1);
+
+        (0..=n)
+            .map(|i| {
+                let t = i as f32 / n as f32;
+                let mt = 1. - t;
+                let v = mt * mt * mt * p0.to_vec2()
+                    + 3. * mt * mt * t * p1.to_vec2()
+                    + 3. * mt * t * t * p2.to_vec2()
+                    + t * t * t * p3.to_vec2();
+                v.to_pos2()
+            })
+            .collect()
+    }
+}
+
+impl PlotItem for Bezier {
+    /// The curves lie within the convex hull of their control points.
+    fn bounds(&self) -> Option<PlotBounds> {
+        let mut bounds = PlotBounds::NOTHING;
+        self.segments
+            .iter()
+            .filter(|s| s.iter().all(|p| p.is_finite()))
+            .flatten()
+            .for_each(|p| bounds.extend_with(*p));
+        Some(bounds).filter(|bounds| bounds.is_finite())
+    }
+
+    fn paint(&self, painter: &mut PlotPainter, transform: &ScreenTransform) {
+        let Self {
+            segments,
+            stroke,
+            show_control_points,
+        } = self;
+
+        segments
+            .iter()
+            .filter(|s| s.iter().any(|p| *p != s[0]))
+            // Like a line, a curve has a gap where a control point is missing.
+            .filter(|s| s.iter().all(|p| p.is_finite()))
+            .for_each(|segment| {
+                // Flatten in screen space so the curve stays smooth at any zoom level.
+                let segment_tf = [
+                    transform.position(&segment[0]),
+                    transform.position(&segment[1]),
+                    transform.position(&segment[2]),
+                    transform.position(&segment[3]),
+                ];
+                painter.add(Shape::line(Self::flatten(&segment_tf), *stroke));
 
-                painter.arrow(p0, p1 - p0, Stroke::new(weight, color));
+                if *show_control_points {
+                    let [p0, p1, p2, p3] = segment_tf;
+                    let faint = Stroke::new(1., stroke.color.linear_multiply(0.3));
+                    painter.line_segment([p1, p2], faint);
+                    painter.line_segment([p0, p1], *stroke);
+                    painter.line_segment([p3, p2], *stroke);
+                    [p0, p3].iter().for_each(|p| {
+                        painter.rect(
+                            Rect::from_center_size(*p, Vec2::new(6., 6.)),
+                            0.,
+                            stroke.color,
+                            Stroke::none(),
+                        )
+                    });
+                    [p1, p2]
+                        .iter()
+                        .for_each(|p| painter.circle(*p, 3., Color32::TRANSPARENT, *stroke));
+                }
             });
     }
 }
+
+/// Build a triangle strip between two polylines given in screen coordinates, with two vertices
+/// per sample. Samples with a non-finite point split the strip, leaving a gap.
+fn band_mesh(lower: &[Pos2], upper: &[Pos2], color: Color32) -> Mesh {
+    let len = lower.len().min(upper.len());
+    chunked_mesh(len, |samples| {
+        // Run on to the first sample of the next part, so the parts join up.
+        let end = (samples.end + 1).min(len);
+        band_strip(
+            &lower[samples.start..end],
+            &upper[samples.start..end],
+            color,
+        )
+    })
+}
+
+fn band_strip(lower: &[Pos2], upper: &[Pos2], color: Color32) -> Mesh {
+    let mut mesh = Mesh::default();
+    let mut connected = false;
+    lower.iter().zip(upper.iter()).for_each(|(l, u)| {
+        if !l.is_finite() || !u.is_finite() {
+            connected = false;
+            return;
+        }
+        let idx = mesh.vertices.len() as u32;
+        mesh.colored_vertex(*l, color);
+        mesh.colored_vertex(*u, color);
+        if connected {
+            mesh.add_triangle(idx - 2, idx - 1, idx);
+            mesh.add_triangle(idx - 1, idx, idx + 1);
+        }
+        connected = true;
+    });
+    mesh
+}
+
+/// A mean line surrounded by shaded bands of ±k standard deviations.
+pub struct MeanStdBand {
+    means: Vec<Value>,
+    stds: Vec<f64>,
+    sigmas: Vec<f64>,
+    color: Color32,
+    weight: f32,
+    fill: Color32,
+}
+
+impl MeanStdBand {
+    /// All series need to have the same length. A NaN standard deviation is treated as 0.
+    pub fn new(xs: Vec<f64>, means: Vec<f64>, stds: Vec<f64>) -> Result<Self, ItemError> {
+        for found in [means.len(), stds.len()].iter() {
+            if *found != xs.len() {
+                return Err(ItemError::LengthMismatch {
+                    expected: xs.len(),
+                    found: *found,
+                });
+            }
+        }
+        Ok(Self {
+            means: xs
+                .into_iter()
+                .zip(means)
+                .map(|(x, mean)| Value::new(x, mean))
+                .collect(),
+            stds,
+            sigmas: vec![1.],
+            color: Color32::WHITE,
+            weight: 1.,
+            fill: Color32::WHITE.linear_multiply(0.2),
+        })
+    }
+
+    pub fn color(mut self, color: Color32) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn weight(mut self, weight: f32) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    pub fn fill_color(mut self, color: Color32) -> Self {
+        self.fill = color;
+        self
+    }
+
+    /// The multiples of the standard deviation to shade. Wider bands are drawn more transparent.
+    pub fn sigmas(mut self, sigmas: Vec<f64>) -> Self {
+        self.sigmas = sigmas;
+        self
+    }
+}
+
+impl MeanStdBand {
+    /// The offset of the band edges from the mean at index `i`, `k` standard deviations away.
+    fn offset(&self, i: usize, k: f64) -> f64 {
+        let std = self.stds[i];
+        k * if std.is_nan() { 0. } else { std }
+    }
+}
+
+impl PlotItem for MeanStdBand {
+    fn points(&self) -> &[Value] {
+        &self.means
+    }
+
+    fn bounds(&self) -> Option<PlotBounds> {
+        let widest = self.sigmas.iter().copied().fold(0., f64::max);
+        let mut bounds = PlotBounds::NOTHING;
+        self.means.iter().enumerate().for_each(|(i, mean)| {
+            let offset = self.offset(i, widest);
+            [mean.y - offset, mean.y + offset]
+                .iter()
+                .map(|y| Value::new(mean.x, *y))
+                .filter(|p| p.is_finite())
+                .for_each(|p| bounds.extend_with(p));
+        });
+        Some(bounds).filter(|bounds| bounds.is_finite())
+    }
+
+    fn paint(&self, painter: &mut PlotPainter, transform: &ScreenTransform) {
+        let Self {
+            means,
+            stds: _,
+            sigmas,
+            color,
+            weight,
+            fill,
+        } = self;
+
+        let mut sigmas = sigmas.clone();
+        sigmas.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        sigmas.iter().enumerate().rev().for_each(|(j, k)| {
+            let edge = |sign: f64| -> Vec<Pos2> {
+                means
+                    .iter()
+                    .enumerate()
+                    .map(|(i, mean)| {
+                        let y = mean.y + sign * self.offset(i, *k);
+                        transform.position(&Value::new(mean.x, y))
+                    })
+                    .collect()
+            };
+            let (lower, upper) = (edge(-1.), edge(1.));
+            let band_fill = fill.linear_multiply(1. / (j + 1) as f32);
+            painter.add(Shape::mesh(band_mesh(&lower, &upper, band_fill)));
+        });
+
+        Line::new(&means[..])
+            .color(*color)
+            .weight(*weight)
+            .paint(painter, transform);
+    }
+}
+
+/// A single point marked with a marker, a leader line and a text label.
+pub struct Callout {
+    point: Value,
+    text: String,
+    shape: MarkerShape,
+    marker_size: f32,
+    offset: Vec2,
+    leader: Stroke,
+    text_color: Color32,
+    background: Color32,
+}
+
+impl Callout {
+    pub fn new(point: impl IntoPlotPoint, text: impl Into<String>) -> Self {
+        Self {
+            point: point.into_plot_point(),
+            text: text.into(),
+            shape: MarkerShape::Circle,
+            marker_size: 3.,
+            offset: vec2(20., -20.),
+            leader: Stroke::new(1., Color32::WHITE),
+            text_color: Color32::WHITE,
+            background: Color32::from_black_alpha(180),
+        }
+    }
+
+    pub fn shape(mut self, shape: MarkerShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    pub fn marker_size(mut self, size: f32) -> Self {
+        self.marker_size = size;
+        self
+    }
+
+    /// Offset of the label from the point in pixels. The offset is mirrored if the label would
+    /// otherwise leave the plot area.
+    pub fn offset(mut self, offset: Vec2) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn leader(mut self, stroke: Stroke) -> Self {
+        self.leader = stroke;
+        self
+    }
+
+    pub fn text_color(mut self, color: Color32) -> Self {
+        self.text_color = color;
+        self
+    }
+
+    pub fn background_color(mut self, color: Color32) -> Self {
+        self.background = color;
+        self
+    }
+}
+
+impl PlotItem for Callout {
+    fn paint(&self, painter: &mut PlotPainter, transform: &ScreenTransform) {
+        let Self {
+            point,
+            text,
+            shape,
+            marker_size,
+            offset,
+            leader,
+            text_color,
+            background,
+        } = self;
+
+        if !point.is_finite() {
+            return;
+        }
+        let p_tf = transform.position(point);
+        let galley = painter.layout_no_wrap(text.clone(), TextStyle::Monospace, *text_color);
+        let mut offset = *offset;
+        let padding = vec2(3., 1.);
+        let label_size = galley.size() + 2. * padding;
+
+        // The label is attached to the end of the leader at the corner facing the point.
+        let label_rect = |offset: Vec2| {
+            let anchor = Align2([
+                if offset.x < 0. {
+                    Align::Max
+                } else {
+                    Align::Min
+                },
+                if offset.y < 0. {
+                    Align::Max
+                } else {
+                    Align::Min
+                },
+            ]);
+            anchor.anchor_rect(Rect::from_min_size(p_tf + offset, label_size))
+        };
+        let bounds = painter.clip_rect();
+        let rect = label_rect(offset);
+        if rect.left() < bounds.left() || rect.right() > bounds.right() {
+            offset.x = -offset.x;
+        }
+        if rect.top() < bounds.top() || rect.bottom() > bounds.bottom() {
+            offset.y = -offset.y;
+        }
+        let rect = label_rect(offset);
+
+        painter.line_segment([p_tf, p_tf + offset], *leader);
+        shape.paint(painter, p_tf, *marker_size, leader.color, *leader);
+        painter.rect(rect, 2., *background, *leader);
+        painter.galley(rect.min + padding, galley);
+    }
+}
+
+/// Maps values in `0..=1` to colors by interpolating between evenly spaced color stops.
+#[derive(Clone, Debug)]
+pub struct Colormap {
+    stops: Vec<Color32>,
+}
+
+impl Colormap {
+    pub fn new(stops: Vec<Color32>) -> Self {
+        assert!(!stops.is_empty(), "A colormap needs at least one color!");
+        Self { stops }
+    }
+
+    pub fn grayscale() -> Self {
+        Self::new(vec![Color32::BLACK, Color32::WHITE])
+    }
+
+    pub fn viridis() -> Self {
+        Self::new(vec![
+            Color32::from_rgb(68, 1, 84),
+            Color32::from_rgb(59, 82, 139),
+            Color32::from_rgb(33, 145, 140),
+            Color32::from_rgb(94, 201, 98),
+            Color32::from_rgb(253, 231, 37),
+        ])
+    }
+
+    /// The color at `t`, which is clamped to `0..=1`.
+    pub fn color(&self, t: f32) -> Color32 {
+        let t = if t.is_nan() { 0. } else { t.clamp(0., 1.) };
+        let scaled = t * (self.stops.len() - 1) as f32;
+        let i = (scaled.floor() as usize).min(self.stops.len() - 1);
+        let j = (i + 1).min(self.stops.len() - 1);
+        let f = scaled - i as f32;
+        let (a, b) = (Rgba::from(self.stops[i]), Rgba::from(self.stops[j]));
+        Color32::from(a * (1. - f) + b * f)
+    }
+}
+
+/// Where a `Colorbar` is drawn.
+pub enum ColorbarPlacement {
+    /// A rectangle in plot coordinates.
+    Plot(PlotBounds),
+    /// A rectangle of the given size in pixels, anchored to a corner of the plot area.
+    Anchored { corner: Align2, size: Vec2 },
+}
+
+/// A gradient strip with ticks mapping colors back to values.
+pub struct Colorbar {
+    colormap: Colormap,
+    range: RangeInclusive<f32>,
+    placement: ColorbarPlacement,
+    horizontal: bool,
+    /// Formats the tick labels. By default they get just enough decimals for the tick spacing.
+    formatter: Option<Box<dyn Fn(f32) -> String>>,
+    color: Color32,
+}
+
+impl Colorbar {
+    pub fn new(colormap: Colormap, range: RangeInclusive<f32>) -> Self {
+        Self {
+            colormap,
+            range,
+            placement: ColorbarPlacement::Anchored {
+                corner: Align2::RIGHT_TOP,
+                size: vec2(15., 150.),
+            },
+            horizontal: false,
+            formatter: None,
+            color: Color32::WHITE,
+        }
+    }
+
+    pub fn placement(mut self, placement: ColorbarPlacement) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    /// Draw the gradient from left to right instead of from bottom to top.
+    pub fn horizontal(mut self, on: bool) -> Self {
+        self.horizontal = on;
+        self
+    }
+
+    pub fn formatter(mut self, formatter: impl Fn(f32) -> String + 'static) -> Self {
+        self.formatter = Some(Box::new(formatter));
+        self
+    }
+
+    /// Color of the frame, the ticks and the tick labels.
+    pub fn color(mut self, color: Color32) -> Self {
+        self.color = color;
+        self
+    }
+}
+
+impl PlotItem for Colorbar {
+    fn paint(&self, painter: &mut PlotPainter, transform: &ScreenTransform) {
+        let Self {
+            colormap,
+            range,
+            placement,
+            horizontal,
+            formatter,
+            color,
+        } = self;
+
+        let rect = match placement {
+            ColorbarPlacement::Plot(bounds) => transform.rect(bounds),
+            ColorbarPlacement::Anchored { corner, size } => {
+                // Leave room for the tick labels on the outer side.
+                let frame = painter.clip_rect().shrink2(vec2(50., 20.));
+                corner.align_size_within_rect(*size, frame)
+            }
+        };
+        // Position along the bar for a fraction of the value range.
+        let along = |t: f32| {
+            if *horizontal {
+                [
+                    pos2(lerp(rect.x_range(), t), rect.top()),
+                    pos2(lerp(rect.x_range(), t), rect.bottom()),
+                ]
+            } else {
+                [
+                    pos2(rect.left(), lerp(rect.bottom()..=rect.top(), t)),
+                    pos2(rect.right(), lerp(rect.bottom()..=rect.top(), t)),
+                ]
+            }
+        };
+
+        let strips = 64;
+        let mut mesh = Mesh::default();
+        (0..=strips).for_each(|i| {
+            let t = i as f32 / strips as f32;
+            let [a, b] = along(t);
+            mesh.colored_vertex(a, colormap.color(t));
+            mesh.colored_vertex(b, colormap.color(t));
+            if i > 0 {
+                let idx = 2 * i as u32;
+                mesh.add_triangle(idx - 2, idx - 1, idx);
+                mesh.add_triangle(idx - 1, idx, idx + 1);
+            }
+        });
+        painter.add(Shape::mesh(mesh));
+        painter.rect_stroke(rect, 0., Stroke::new(1., *color));
+
+        // In f64, so the ticks of a narrow range far from zero don't collapse.
+        let (start, end) = (*range.start() as f64, *range.end() as f64);
+        let increment = tick_increment((end - start).abs(), 5);
+        let magnitude = start.abs().max(end.abs());
+        let label = |value: f64| match formatter {
+            Some(formatter) => formatter(value as f32),
+            None => format_tick(value, increment, magnitude),
+        };
+        multiples(start.min(end), start.max(end), increment, MAX_TICKS)
+            .into_iter()
+            .for_each(|value| {
+                let [_, b] = along(remap(value, start..=end, 0.0..=1.0) as f32);
+                let (tick, label_pos, anchor) = if *horizontal {
+                    ([b, b + 4. * Vec2::Y], b + 6. * Vec2::Y, Align2::CENTER_TOP)
+                } else {
+                    ([b, b + 4. * Vec2::X], b + 6. * Vec2::X, Align2::LEFT_CENTER)
+                };
+                painter.line_segment(tick, Stroke::new(1., *color));
+                painter.text(
+                    label_pos,
+                    anchor,
+                    label(value),
+                    TextStyle::Monospace,
+                    *color,
+                );
+            });
+    }
+}
+
+/// Bin points into a regular grid and color the cells by their count.
+pub struct Histogram2d<'a> {
+    points: PlotPoints<'a>,
+    extent: PlotBounds,
+    bins: [usize; 2],
+    colormap: Colormap,
+    log_scale: bool,
+    min_count: usize,
+}
+
+impl<'a> Histogram2d<'a> {
+    /// Points outside of `extent` are ignored.
+    pub fn new(points: impl Into<PlotPoints<'a>>, extent: PlotBounds) -> Self {
+        Self {
+            points: points.into(),
+            extent,
+            bins: [50, 50],
+            colormap: Colormap::viridis(),
+            log_scale: false,
+            min_count: 1,
+        }
+    }
+
+    /// Number of bins along the x and y axis.
+    pub fn bins(mut self, x_bins: usize, y_bins: usize) -> Self {
+        self.bins = [x_bins.max(1), y_bins.max(1)];
+        self
+    }
+
+    pub fn colormap(mut self, colormap: Colormap) -> Self {
+        self.colormap = colormap;
+        self
+    }
+
+    /// Map the logarithm of the counts to colors.
+    pub fn log_scale(mut self, on: bool) -> Self {
+        self.log_scale = on;
+        self
+    }
+
+    /// Cells with fewer points are left transparent.
+    pub fn min_count(mut self, min_count: usize) -> Self {
+        self.min_count = min_count;
+        self
+    }
+
+    fn counts(&self) -> Vec<usize> {
+        let [nx, ny] = self.bins;
+        let mut counts = vec![0; nx * ny];
+        let bin = |value: f64, range: RangeInclusive<f64>, n: usize| {
+            let t = remap(value, range, 0.0..=n as f64);
+            // Points exactly on the upper edge belong to the last bin.
+            (t as usize).min(n - 1)
+        };
+        self.points
+            .iter()
+            .filter(|p| p.is_finite() && self.extent.contains(**p))
+            .for_each(|p| {
+                let i = bin(p.x, self.extent.x_range(), nx);
+                let j = bin(p.y, self.extent.y_range(), ny);
+                counts[j * nx + i] += 1;
+            });
+        counts
+    }
+}
+
+impl PlotItem for Histogram2d<'_> {
+    /// The whole grid, since points outside of it aren't shown.
+    fn bounds(&self) -> Option<PlotBounds> {
+        Some(self.extent)
+    }
+
+    fn paint(&self, painter: &mut PlotPainter, transform: &ScreenTransform) {
+        let counts = self.counts();
+        let Self {
+            extent,
+            bins: [nx, ny],
+            colormap,
+            log_scale,
+            min_count,
+            ..
+        } = self;
+
+        let scale = |count: usize| {
+            if *log_scale {
+                (count as f32).ln_1p()
+            } else {
+                count as f32
+            }
+        };
+        let max = scale(counts.iter().copied().max().unwrap_or(0)).max(f32::EPSILON);
+
+        let cell_size = Value::new(extent.width() / *nx as f64, extent.height() / *ny as f64);
+        let mut mesh = Mesh::default();
+        counts
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count >= (*min_count).max(1))
+            .for_each(|(k, count)| {
+                let min = Value::new(
+                    extent.min.x + (k % *nx) as f64 * cell_size.x,
+                    extent.min.y + (k / *nx) as f64 * cell_size.y,
+                );
+                let max_corner = Value::new(min.x + cell_size.x, min.y + cell_size.y);
+                let color = colormap.color(scale(*count) / max);
+                let idx = mesh.vertices.len() as u32;
+                mesh.colored_vertex(transform.position(&min), color);
+                mesh.colored_vertex(transform.position(&Value::new(max_corner.x, min.y)), color);
+                mesh.colored_vertex(transform.position(&Value::new(min.x, max_corner.y)), color);
+                mesh.colored_vertex(transform.position(&max_corner), color);
+                mesh.add_triangle(idx, idx + 1, idx + 2);
+                mesh.add_triangle(idx + 1, idx + 2, idx + 3);
+            });
+        painter.add(Shape::mesh(mesh));
+    }
+}
+
+/// Vertical lines from a baseline to each value, with a marker at the tip.
+pub struct Stem<'a> {
+    points: PlotPoints<'a>,
+    baseline: f64,
+    stroke: Stroke,
+    shape: MarkerShape,
+    marker_size: f32,
+    marker_color: Color32,
+    baseline_stroke: Option<Stroke>,
+    name: Option<String>,
+}
+
+impl<'a> Stem<'a> {
+    pub fn new(points: impl Into<PlotPoints<'a>>) -> Self {
+        Self {
+            points: points.into(),
+            baseline: 0.,
+            stroke: Stroke::new(1., Color32::WHITE),
+            shape: MarkerShape::Circle,
+            marker_size: 3.,
+            marker_color: Color32::WHITE,
+            baseline_stroke: None,
+            name: None,
+        }
+    }
+
+    /// Name of the stems, shown in the legend.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// The y value the stems start from.
+    pub fn baseline(mut self, baseline: impl Into<f64>) -> Self {
+        self.baseline = baseline.into();
+        self
+    }
+
+    pub fn stroke(mut self, stroke: Stroke) -> Self {
+        self.stroke = stroke;
+        self
+    }
+
+    pub fn shape(mut self, shape: MarkerShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    pub fn marker_size(mut self, size: f32) -> Self {
+        self.marker_size = size;
+        self
+    }
+
+    pub fn marker_color(mut self, color: Color32) -> Self {
+        self.marker_color = color;
+        self
+    }
+
+    /// Draw a horizontal line at the baseline, spanning the x extent of the data.
+    pub fn baseline_stroke(mut self, stroke: Stroke) -> Self {
+        self.baseline_stroke = Some(stroke);
+        self
+    }
+}
+
+impl PlotItem for Stem<'_> {
+    fn points(&self) -> &[Value] {
+        &self.points
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    fn color(&self) -> Color32 {
+        self.marker_color
+    }
+
+    fn highlight(&mut self) {
+        self.stroke.width *= 2.;
+        self.marker_size *= 1.5;
+    }
+
+    fn paint(&self, painter: &mut PlotPainter, transform: &ScreenTransform) {
+        let Self {
+            points,
+            baseline,
+            stroke,
+            shape,
+            marker_size,
+            marker_color,
+            baseline_stroke,
+            name: _,
+        } = self;
+
+        let points: Vec<Value> = points.iter().copied().filter(|p| p.is_finite()).collect();
+
+        if let Some(baseline_stroke) = baseline_stroke {
+            let min_x = points.iter().map(|p| p.x).fold(f64::INFINITY, f64::min);
+            let max_x = points.iter().map(|p| p.x).fold(f64::NEG_INFINITY, f64::max);
+            if min_x <= max_x {
+                painter.line_segment(
+                    [
+                        transform.position(&Value::new(min_x, *baseline)),
+                        transform.position(&Value::new(max_x, *baseline)),
+                    ],
+                    *baseline_stroke,
+                );
+            }
+        }
+
+        points.iter().for_each(|p| {
+            let p_tf = transform.position(p);
+            painter.line_segment(
+                [transform.position(&Value::new(p.x, *baseline)), p_tf],
+                *stroke,
+            );
+            shape.paint(
+                painter,
+                p_tf,
+                *marker_size,
+                *marker_color,
+                Stroke::new(1., *marker_color),
+            );
+        });
+    }
+}
+
+/// A horizontal line spanning the whole plot, e.g. to mark a threshold.
+pub struct HLine {
+    y: f64,
+    stroke: Stroke,
+    name: Option<String>,
+    label: Option<String>,
+    id: Option<Id>,
+}
+
+impl HLine {
+    pub fn new(y: impl Into<f64>) -> Self {
+        Self {
+            y: y.into(),
+            stroke: Stroke::new(1., Color32::WHITE),
+            name: None,
+            label: None,
+            id: None,
+        }
+    }
+
+    pub fn stroke(mut self, stroke: Stroke) -> Self {
+        self.stroke = stroke;
+        self
+    }
+
+    /// Name of the line, shown in the legend.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Text shown above the right end of the line.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Let the line be moved up and down by dragging it. The new value is reported in
+    /// `PlotResponse::dragged_line` under this `id`.
+    pub fn draggable(mut self, id: impl std::hash::Hash) -> Self {
+        self.id = Some(Id::new(id));
+        self
+    }
+}
+
+impl PlotItem for HLine {
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    fn color(&self) -> Color32 {
+        self.stroke.color
+    }
+
+    fn highlight(&mut self) {
+        self.stroke.width *= 2.;
+    }
+
+    fn drag_line(&self) -> Option<(Id, DragAxes, f64)> {
+        self.id.map(|id| (id, DragAxes::Y, self.y))
+    }
+
+    fn paint(&self, painter: &mut PlotPainter, transform: &ScreenTransform) {
+        let Self {
+            y,
+            stroke,
+            name: _,
+            label,
+            id: _,
+        } = self;
+
+        let rect = painter.clip_rect();
+        let y = transform.position(&Value::new(0., *y)).y;
+        painter.line_segment([pos2(rect.left(), y), pos2(rect.right(), y)], *stroke);
+        if let Some(label) = label {
+            painter.text(
+                pos2(rect.right() - 5., y - 2.),
+                Align2::RIGHT_BOTTOM,
+                label,
+                TextStyle::Small,
+                stroke.color,
+            );
+        }
+    }
+}
+
+/// A vertical line spanning the whole plot, e.g. to mark an event.
+pub struct VLine {
+    x: f64,
+    stroke: Stroke,
+    name: Option<String>,
+    label: Option<String>,
+    id: Option<Id>,
+}
+
+impl VLine {
+    pub fn new(x: impl Into<f64>) -> Self {
+        Self {
+            x: x.into(),
+            stroke: Stroke::new(1., Color32::WHITE),
+            name: None,
+            label: None,
+            id: None,
+        }
+    }
+
+    pub fn stroke(mut self, stroke: Stroke) -> Self {
+        self.stroke = stroke;
+        self
+    }
+
+    /// Name of the line, shown in the legend.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Text shown next to the top end of the line.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Let the line be moved left and right by dragging it. The new value is reported in
+    /// `PlotResponse::dragged_line` under this `id`.
+    pub fn draggable(mut self, id: impl std::hash::Hash) -> Self {
+        self.id = Some(Id::new(id));
+        self
+    }
+}
+
+impl PlotItem for VLine {
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    fn color(&self) -> Color32 {
+        self.stroke.color
+    }
+
+    fn highlight(&mut self) {
+        self.stroke.width *= 2.;
+    }
+
+    fn drag_line(&self) -> Option<(Id, DragAxes, f64)> {
+        self.id.map(|id| (id, DragAxes::X, self.x))
+    }
+
+    fn paint(&self, painter: &mut PlotPainter, transform: &ScreenTransform) {
+        let Self {
+            x,
+            stroke,
+            name: _,
+            label,
+            id: _,
+        } = self;
+
+        let rect = painter.clip_rect();
+        let x = transform.position(&Value::new(*x, 0.)).x;
+        painter.line_segment([pos2(x, rect.top()), pos2(x, rect.bottom())], *stroke);
+        if let Some(label) = label {
+            painter.text(
+                pos2(x + 2., rect.top() + 5.),
+                Align2::LEFT_TOP,
+                label,
+                TextStyle::Small,
+                stroke.color,
+            );
+        }
+    }
+}
@@ -1,2 +1,5 @@
 pub mod items;
 pub mod plot;
+pub mod series;
+mod ticks;
+pub mod time;
@@ -1,45 +1,518 @@
-use eframe::egui::*;
-use std::{collections::HashMap, ops::RangeInclusive};
+use eframe::egui::{epaint::TextShape, *};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    ops::RangeInclusive,
+};
 
-use super::items::PlotItem;
+use super::items::{DragAxes, PlotBounds, PlotItem, PlotPainter, Value};
+use super::ticks::{
+    format_percent, format_si, format_significant, format_tick, generate_ticks, pi_increment,
+    tick_increment, Tick, TickConfig,
+};
+use super::time::TimeFormat;
 
 pub struct PlotUi<'p> {
-    painter: &'p mut Painter,
-    plot_to_screen: &'p dyn Fn(&Pos2) -> Pos2,
-    mouse_position: Option<Pos2>,
+    painter: PlotPainter<'p>,
+    /// Whether the shapes of the last frame are replayed instead of painting the items, see
+    /// `Plot::data_version`.
+    replaying: bool,
+    transform: ScreenTransform,
+    /// The transform of the secondary y axis, if the plot has one.
+    secondary_transform: Option<ScreenTransform>,
+    mouse_position: Option<Value>,
     hovered: bool,
+    /// Collects the extent of the data while following, see `Plot::follow_x`.
+    data: Option<DataExtent>,
+    x_window: RangeInclusive<f64>,
+    /// Names and colors of the named items, in the order they were added.
+    legend_entries: Vec<(String, Color32)>,
+    hidden_items: &'p HashSet<String>,
+    highlighted_item: Option<&'p str>,
+    /// Looks for the data point closest to the pointer, see `Plot::hover_tooltip`.
+    hover: Option<HoverSearch>,
+    /// Collects the values of the named items at the pointer, see `Plot::hover_readout`.
+    readout: Option<HoverReadout>,
+    /// Where the plot was clicked this frame, in screen coordinates.
+    click_pos: Option<Pos2>,
+    /// The point closest to `click_pos` and its distance to it.
+    picked_point: Option<(f32, SelectedPoint)>,
+    selected_points: &'p [SelectedPoint],
+    /// Screen positions and pick radii of the selected points.
+    selection_marks: Vec<(Pos2, f32)>,
+    hover_pos: Option<Pos2>,
+    /// The topmost item under `hover_pos`, see `PlotItem::hit_test`.
+    hovered_item: Option<Id>,
+    /// The draggable point or line closest to `hover_pos`, its distance to it and the cursor
+    /// to show while hovering it.
+    hovered_handle: Option<(f32, (Id, usize), CursorIcon)>,
+    /// The point or line being dragged and the pointer position, in screen coordinates.
+    grabbed_point: Option<((Id, usize), Pos2)>,
+    dragged_point: Option<SelectedPoint>,
+    dragged_line: Option<(Id, f64)>,
+    /// Overrides the cursor while a line is dragged.
+    cursor: Option<CursorIcon>,
+    /// Collects the bounds of the data on the primary axes, see `PlotCtx::zoom_to_fit`.
+    fit_bounds: Option<PlotBounds>,
+    /// A finished rectangular selection, in screen coordinates.
+    select_rect: Option<Rect>,
+    /// The pickable points inside `select_rect`.
+    rect_selected: Vec<SelectedPoint>,
+    lock_x: bool,
+    lock_y: bool,
 }
 
 impl<'p> PlotUi<'p> {
-    pub fn add<D: PlotItem>(&mut self, item: D) {
-        item.paint(self.painter, self.plot_to_screen);
+    pub fn add<D: PlotItem>(&mut self, mut item: D) {
+        self.add_item(&mut item, true);
     }
 
-    pub fn plot_mouse_position(&self) -> Option<Pos2> {
+    fn add_item<D: PlotItem + ?Sized>(&mut self, item: &mut D, highlight: bool) {
+        if self.prepare(item, highlight) {
+            let item_bounds = item.bounds();
+            if let Some(data) = &mut self.data {
+                data.include(item.points(), &self.x_window);
+                if let Some(item_bounds) = item_bounds {
+                    data.include_bounds(&item_bounds, &self.x_window);
+                }
+            }
+            if let Some(bounds) = &mut self.fit_bounds {
+                item.points()
+                    .iter()
+                    .filter(|p| p.is_finite())
+                    .for_each(|p| bounds.extend_with(*p));
+                if let Some(item_bounds) = item_bounds {
+                    bounds.extend_with(item_bounds.min);
+                    bounds.extend_with(item_bounds.max);
+                }
+            }
+            let transform = self.transform;
+            self.interact(item, &transform);
+            if !self.replaying {
+                item.cull(transform.bounds());
+                item.paint(&mut self.painter, &transform);
+            }
+        }
+    }
+
+    /// Add an item whose y values refer to the secondary y axis.
+    /// Falls back to the primary axis if the plot has no secondary axis.
+    pub fn add_secondary<D: PlotItem>(&mut self, mut item: D) {
+        if self.prepare(&mut item, true) {
+            let transform = self.secondary_transform.unwrap_or(self.transform);
+            self.interact(&item, &transform);
+            if !self.replaying {
+                item.cull(transform.bounds());
+                item.paint(&mut self.painter, &transform);
+            }
+        }
+    }
+
+    /// Register a named item in the legend. Returns false if it is hidden.
+    fn prepare<D: PlotItem + ?Sized>(&mut self, item: &mut D, highlight: bool) -> bool {
+        let name = match item.name() {
+            Some(name) => name.to_string(),
+            None => return true,
+        };
+        if !self.legend_entries.iter().any(|(entry, _)| *entry == name) {
+            self.legend_entries.push((name.clone(), item.color()));
+        }
+        if self.hidden_items.contains(&name) {
+            return false;
+        }
+        if highlight && self.highlighted_item == Some(name.as_str()) {
+            item.highlight();
+        }
+        true
+    }
+
+    /// Hit-test the pointer against the points of an item, in screen space.
+    fn interact<D: PlotItem + ?Sized>(&mut self, item: &D, transform: &ScreenTransform) {
+        if let Some(hover) = &mut self.hover {
+            hover.include(item, transform);
+        }
+        if let Some(readout) = &mut self.readout {
+            readout.include(item);
+        }
+        if let Some((id, axes, value)) = item.drag_line() {
+            self.interact_line(id, axes, value, transform);
+        }
+        // Later items are painted on top, so they win.
+        if let Some(pointer) = self
+            .hover_pos
+            .filter(|pos| self.painter.clip_rect().contains(*pos))
+        {
+            if let Some(id) = item.hit_test(pointer, transform) {
+                self.hovered_item = Some(id);
+            }
+        }
+        let id = match item.id() {
+            Some(id) => id,
+            None => return,
+        };
+        let points = item.points();
+        let radius = item.pick_radius();
+        if let Some(rect) = self.select_rect {
+            points
+                .iter()
+                .enumerate()
+                .filter(|(_, p)| rect.contains(transform.position(p)))
+                .for_each(|(index, p)| {
+                    self.rect_selected.push(SelectedPoint {
+                        item: id,
+                        index,
+                        value: *p,
+                    })
+                });
+        }
+        self.selected_points
+            .iter()
+            .filter(|selected| selected.item == id)
+            .filter_map(|selected| points.get(selected.index))
+            .for_each(|p| self.selection_marks.push((transform.position(p), radius)));
+        if let Some(click_pos) = self.click_pos {
+            points.iter().enumerate().for_each(|(index, p)| {
+                let distance = transform.position(p).distance(click_pos);
+                let closer = match &self.picked_point {
+                    Some((closest, _)) => distance < *closest,
+                    None => true,
+                };
+                if distance <= radius && closer {
+                    let point = SelectedPoint {
+                        item: id,
+                        index,
+                        value: *p,
+                    };
+                    self.picked_point = Some((distance, point));
+                }
+            });
+        }
+
+        let drag_axes = match item.drag_axes() {
+            Some(axes) => axes,
+            None => return,
+        };
+        match self.grabbed_point {
+            Some(((grabbed, index), pointer)) if grabbed == id => {
+                if let Some(p) = points.get(index) {
+                    let mut value = transform.value(&pointer);
+                    if self.lock_x || drag_axes == DragAxes::Y {
+                        value.x = p.x;
+                    }
+                    if self.lock_y || drag_axes == DragAxes::X {
+                        value.y = p.y;
+                    }
+                    self.selection_marks
+                        .push((transform.position(&value), radius));
+                    self.dragged_point = Some(SelectedPoint {
+                        item: id,
+                        index,
+                        value,
+                    });
+                }
+            }
+            Some(_) => {}
+            None => {
+                if let Some(hover_pos) = self.hover_pos {
+                    points.iter().enumerate().for_each(|(index, p)| {
+                        let distance = transform.position(p).distance(hover_pos);
+                        let closer = match &self.hovered_handle {
+                            Some((closest, _, _)) => distance < *closest,
+                            None => true,
+                        };
+                        if distance <= radius && closer {
+                            self.hovered_handle = Some((distance, (id, index), CursorIcon::Grab));
+                        }
+                    });
+                }
+            }
+        }
+    }
+
+    /// Hit-test the pointer against a draggable horizontal or vertical line.
+    fn interact_line(&mut self, id: Id, axes: DragAxes, value: f64, transform: &ScreenTransform) {
+        let horizontal = axes == DragAxes::Y;
+        if (horizontal && self.lock_y) || (!horizontal && self.lock_x) {
+            return;
+        }
+        let (cursor, pixel): (_, fn(Pos2) -> f32) = if horizontal {
+            (CursorIcon::ResizeVertical, |pos| pos.y)
+        } else {
+            (CursorIcon::ResizeHorizontal, |pos| pos.x)
+        };
+        match self.grabbed_point {
+            Some(((grabbed, _), pointer)) if grabbed == id => {
+                self.cursor = Some(cursor);
+                let value = transform.value(&pointer);
+                let value = if horizontal { value.y } else { value.x };
+                self.dragged_line = Some((id, value));
+            }
+            Some(_) => {}
+            None => {
+                if let Some(hover_pos) = self.hover_pos {
+                    let line = pixel(transform.position(&Value::new(value, value)));
+                    let distance = (pixel(hover_pos) - line).abs();
+                    let closer = match &self.hovered_handle {
+                        Some((closest, _, _)) => distance < *closest,
+                        None => true,
+                    };
+                    if distance <= LINE_PICK_DISTANCE && closer {
+                        self.hovered_handle = Some((distance, (id, 0), cursor));
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn plot_mouse_position(&self) -> Option<Value> {
         self.mouse_position
     }
 
     pub fn plot_hovered(&self) -> bool {
         self.hovered
     }
+
+    /// Maps between plot and screen coordinates on the primary axes.
+    pub fn transform(&self) -> &ScreenTransform {
+        &self.transform
+    }
 }
 
-#[derive(Clone, Copy)]
-enum AxisScaling {
-    Linear,
-    Logarithmic,
+/// The built-in entries of the context menu and the buttons of the toolbar.
+enum PlotAction {
+    ResetView,
+    AutoFit,
+    ZoomIn,
+    ZoomOut,
+    ToggleGrid,
+    /// Copy the given position, in plot coordinates.
+    CopyPosition(Value),
+    CopyView,
 }
 
-impl Default for AxisScaling {
-    fn default() -> Self {
-        Self::Linear
+/// How far a button of the toolbar zooms, as a fraction of the visible extent.
+const TOOLBAR_ZOOM_STEP: f64 = 0.1;
+
+/// A value copied out of a plot, see `Plot::copy_formatter`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CopiedValue {
+    /// The pointer position, in plot coordinates.
+    Position(Value),
+    /// The visible ranges of the primary axes.
+    View {
+        x_range: RangeInclusive<f64>,
+        y_range: RangeInclusive<f64>,
+    },
+}
+
+/// Distance in pixels from a draggable line within which it can be grabbed.
+const LINE_PICK_DISTANCE: f32 = 4.;
+
+/// Number of frames of a drag from which the velocity of the pan inertia is computed.
+const PAN_HISTORY: usize = 5;
+
+/// Speed in pixels per second below which the pan inertia stops.
+const INERTIA_MIN_SPEED: f32 = 20.;
+
+/// Time in seconds in which the pan inertia slows down to about a third of its speed.
+const INERTIA_TIME_CONSTANT: f32 = 0.3;
+
+/// A point that was selected by clicking it, see `Scatter::interactable`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SelectedPoint {
+    /// The id of the item the point belongs to.
+    pub item: Id,
+    /// The index of the point within the item.
+    pub index: usize,
+    /// The point in plot coordinates.
+    pub value: Value,
+}
+
+/// What happened in a plot while it was shown.
+pub struct PlotResponse<R> {
+    /// The value returned by the closure passed to `Plot::show`.
+    pub inner: R,
+    pub response: Response,
+    /// The pointer position in plot coordinates, if the pointer is over the plot area.
+    pub pointer_position: Option<Value>,
+    /// Whether the pointer is over the plot area.
+    pub hovered: bool,
+    /// The visible part of the plot, in plot coordinates of the primary axes.
+    pub bounds: PlotBounds,
+    /// Whether the visible part of the plot changed this frame, e.g. by panning or zooming.
+    pub view_changed: bool,
+    /// The points selected by clicking or with `Plot::rect_selection`, in the order they were
+    /// selected. Shift-click adds or removes points, clicking next to all points clears the
+    /// selection.
+    pub selected_points: Vec<SelectedPoint>,
+    /// The point being dragged and where it was dragged to, see `Scatter::draggable`. The
+    /// item's data has to be updated for the point to move.
+    pub dragged_point: Option<SelectedPoint>,
+    /// The id of the line being dragged and its new value, see `HLine::draggable`.
+    pub dragged_line: Option<(Id, f64)>,
+    /// The id of the topmost item under the pointer, see `Line::id` and `Polygon::id`.
+    pub hovered_item: Option<Id>,
+    /// The id of the item that was clicked.
+    pub clicked_item: Option<Id>,
+    /// Whether the x axis is logarithmic, which can be toggled with `Plot::allow_scale_toggle`.
+    pub x_log: bool,
+    /// Whether the y axis is logarithmic.
+    pub y_log: bool,
+}
+
+/// The data point closest to the pointer, in screen space.
+struct NearestPoint {
+    distance: f32,
+    name: Option<String>,
+    value: Value,
+    screen_pos: Pos2,
+}
+
+/// How many pixels closer another point has to be to replace the last nearest point.
+const SNAP_HYSTERESIS: f32 = 3.;
+
+/// Search for the data point closest to the pointer among the added items.
+struct HoverSearch {
+    pointer: Pos2,
+    /// The x values that are within `radius` of the pointer horizontally.
+    x_window: RangeInclusive<f64>,
+    radius: f32,
+    nearest: Option<NearestPoint>,
+    /// The point found in the last frame, which is preferred to avoid flickering between
+    /// points at almost the same distance.
+    previous: Option<Value>,
+}
+
+impl HoverSearch {
+    fn include<D: PlotItem + ?Sized>(&mut self, item: &D, transform: &ScreenTransform) {
+        let points = item.points();
+        // Data sorted by x only needs to be searched within the x window.
+        let candidates = if item.sorted_by_x() {
+            let start = points.partition_point(|p| p.x < *self.x_window.start());
+            let end = points.partition_point(|p| p.x <= *self.x_window.end());
+            &points[start..end.max(start)]
+        } else {
+            points
+        };
+        candidates.iter().filter(|p| p.is_finite()).for_each(|p| {
+            let screen_pos = transform.position(p);
+            let mut distance = screen_pos.distance(self.pointer);
+            if distance > self.radius {
+                return;
+            }
+            if self.previous == Some(*p) {
+                distance -= SNAP_HYSTERESIS;
+            }
+            let closer = match &self.nearest {
+                Some(nearest) => distance < nearest.distance,
+                None => true,
+            };
+            if closer {
+                self.nearest = Some(NearestPoint {
+                    distance,
+                    name: item.name().map(|name| name.to_string()),
+                    value: *p,
+                    screen_pos,
+                });
+            }
+        });
     }
 }
 
-#[derive(Clone, Copy)]
-struct AxisRange {
-    start: f32,
-    end: f32,
+/// The values of the named items at the x position of the pointer.
+struct HoverReadout {
+    x: f64,
+    /// Name, color and value of each named item, `None` if it has no value at `x`.
+    rows: Vec<(String, Color32, Option<f64>)>,
+}
+
+impl HoverReadout {
+    fn include<D: PlotItem + ?Sized>(&mut self, item: &D) {
+        if let Some(name) = item.name() {
+            let value = value_at(item.points(), item.sorted_by_x(), self.x);
+            self.rows.push((name.to_string(), item.color(), value));
+        }
+    }
+}
+
+/// The y value of the line through `points` at `x`, interpolated between the two points
+/// around it.
+fn value_at(points: &[Value], sorted: bool, x: f64) -> Option<f64> {
+    let (a, b) = if sorted {
+        let i = points.partition_point(|p| p.x < x);
+        match points.get(i) {
+            Some(p) if p.x == x => (*p, *p),
+            Some(p) if i > 0 => (points[i - 1], *p),
+            _ => return None,
+        }
+    } else {
+        points
+            .windows(2)
+            .map(|w| (w[0], w[1]))
+            .find(|(a, b)| a.x.min(b.x) <= x && x <= a.x.max(b.x))
+            .or_else(|| points.iter().find(|p| p.x == x).map(|p| (*p, *p)))?
+    };
+    let t = if a.x == b.x {
+        0.
+    } else {
+        (x - a.x) / (b.x - a.x)
+    };
+    Some(a.y + t * (b.y - a.y)).filter(|y| y.is_finite())
+}
+
+/// Whether the x values of `points` never decrease.
+pub(crate) fn is_sorted_by_x(points: &[Value]) -> bool {
+    points.windows(2).all(|w| w[0].x <= w[1].x)
+}
+
+/// The newest x value and the y extent within the visible x range of the data added to a plot.
+#[derive(Clone, Copy, Default)]
+struct DataExtent {
+    latest_x: Option<f64>,
+    window_y: Option<(f64, f64)>,
+}
+
+impl DataExtent {
+    fn include(&mut self, points: &[Value], x_window: &RangeInclusive<f64>) {
+        points
+            .iter()
+            .filter(|p| p.x.is_finite() && p.y.is_finite())
+            .for_each(|p| {
+                self.latest_x = Some(self.latest_x.map_or(p.x, |x| x.max(p.x)));
+                if x_window.contains(&p.x) {
+                    self.window_y = Some(match self.window_y {
+                        Some((min, max)) => (min.min(p.y), max.max(p.y)),
+                        None => (p.y, p.y),
+                    });
+                }
+            });
+    }
+
+    /// Include an area, which counts as within the x window if it overlaps it.
+    fn include_bounds(&mut self, bounds: &PlotBounds, x_window: &RangeInclusive<f64>) {
+        if !bounds.is_finite() {
+            return;
+        }
+        self.latest_x = Some(self.latest_x.map_or(bounds.max.x, |x| x.max(bounds.max.x)));
+        if bounds.min.x <= *x_window.end() && bounds.max.x >= *x_window.start() {
+            self.window_y = Some(match self.window_y {
+                Some((min, max)) => (min.min(bounds.min.y), max.max(bounds.max.y)),
+                None => (bounds.min.y, bounds.max.y),
+            });
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum AxisScaling {
+    #[default]
+    Linear,
+    Logarithmic,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct AxisRange {
+    pub(crate) start: f64,
+    pub(crate) end: f64,
     scaling: AxisScaling,
 }
 
@@ -54,7 +527,7 @@ impl Default for AxisRange {
 }
 
 impl AxisRange {
-    fn new(range: RangeInclusive<f32>) -> Self {
+    pub(crate) fn new(range: RangeInclusive<f64>) -> Self {
         Self {
             start: *range.start(),
             end: *range.end(),
@@ -62,430 +535,4245 @@ impl AxisRange {
         }
     }
 
-    fn extent(&self) -> f32 {
+    #[cfg(test)]
+    pub(crate) fn logarithmic(range: RangeInclusive<f64>) -> Self {
+        Self {
+            scaling: AxisScaling::Logarithmic,
+            ..Self::new(range)
+        }
+    }
+
+    pub(crate) fn extent(&self) -> f64 {
         self.end - self.start
     }
 
-    fn middle(&self) -> f32 {
+    fn middle(&self) -> f64 {
         (self.start + self.end) / 2.
     }
 
-    fn translate(&mut self, delta: f32) {
-        self.start += delta;
-        self.end += delta;
+    pub(crate) fn is_log(&self) -> bool {
+        self.scaling == AxisScaling::Logarithmic
     }
 
-    fn zoom(&mut self, amount: f32, center: f32) {
-        self.start -= amount * center * self.extent();
-        self.end += amount * (1. - center) * self.extent();
+    /// Map a value into the space in which the axis is linear.
+    fn scale(&self, value: f64) -> f64 {
+        match self.scaling {
+            AxisScaling::Linear => value,
+            AxisScaling::Logarithmic => value.log10(),
+        }
     }
 
-    fn pixel_to_axis(&self, pixel_range: RangeInclusive<f32>, pixel: f32, flip: bool) -> f32 {
-        let pixel_tf = if flip {
-            remap(pixel, pixel_range.clone(), self.end..=self.start)
-        } else {
-            remap(pixel, pixel_range.clone(), self.start..=self.end)
-        };
+    /// Inverse of `scale`.
+    fn unscale(&self, scaled: f64) -> f64 {
         match self.scaling {
-            AxisScaling::Linear => pixel_tf,
-            AxisScaling::Logarithmic => {
-                let den = (self.end / self.start).log10();
-                let t = (pixel_tf - self.start) / self.extent();
-                (t * den).powi(10) * self.start
-            }
+            AxisScaling::Linear => scaled,
+            AxisScaling::Logarithmic => 10f64.powf(scaled),
         }
     }
 
-    fn axis_to_pixel(&self, pixel_range: RangeInclusive<f32>, axis_pos: f32, flip: bool) -> f32 {
-        match self.scaling {
-            AxisScaling::Linear => {
-                if flip {
-                    remap(axis_pos, self.end..=self.start, pixel_range)
-                } else {
-                    remap(axis_pos, self.start..=self.end, pixel_range)
-                }
+    fn scaled_range(&self) -> RangeInclusive<f64> {
+        self.scale(self.start)..=self.scale(self.end)
+    }
+
+    /// A logarithmic axis can only show positive values. Clamp the range instead of producing NaN.
+    fn sanitize(&mut self) {
+        if self.is_log() {
+            if self.end <= 0. || !self.end.is_finite() {
+                self.end = 10.;
             }
-            AxisScaling::Logarithmic => {
-                let t = (axis_pos / self.start).log(self.end / self.start);
-                if flip {
-                    lerp(self.end..=self.start, t)
-                } else {
-                    lerp(self.start..=self.end, t)
-                }
+            if self.start <= 0. || self.start.is_nan() || self.start >= self.end {
+                self.start = self.end / 1000.;
             }
         }
     }
-}
 
-pub struct Axis {
-    label: String,
-    range: AxisRange,
-}
+    /// Translate by `delta` in scaled space, i.e. by decades for a logarithmic axis.
+    fn translate(&mut self, delta: f64) {
+        self.start = self.unscale(self.scale(self.start) + delta);
+        self.end = self.unscale(self.scale(self.end) + delta);
+    }
 
-impl Default for Axis {
-    fn default() -> Self {
-        Self {
-            label: "".to_string(),
-            range: AxisRange::new((-10.)..=10.),
+    fn zoom(&mut self, amount: f64, center: f64) {
+        let (start, end) = (self.scale(self.start), self.scale(self.end));
+        let extent = end - start;
+        self.start = self.unscale(start - amount * center * extent);
+        self.end = self.unscale(end + amount * (1. - center) * extent);
+    }
+
+    /// Show `range`, grown or shrunk around its middle to keep the extent within `extent_limits`.
+    fn set_limited(&mut self, range: RangeInclusive<f64>, extent_limits: RangeInclusive<f64>) {
+        let middle = (range.start() + range.end()) / 2.;
+        let extent = (range.end() - range.start())
+            .max(*extent_limits.start())
+            .min(*extent_limits.end());
+        self.start = middle - extent / 2.;
+        self.end = middle + extent / 2.;
+    }
+
+    /// Shift the range back inside `bounds`, or center it on them if it is larger. The extent is
+    /// kept so an equal aspect ratio survives the clamp.
+    fn clamp_to(&mut self, bounds: RangeInclusive<f64>) {
+        if self.is_log() && *bounds.start() <= 0. {
+            return;
         }
+        let (min, max) = (self.scale(*bounds.start()), self.scale(*bounds.end()));
+        let (start, end) = (self.scale(self.start), self.scale(self.end));
+        let delta = if end - start >= max - min {
+            (min + max) / 2. - (start + end) / 2.
+        } else if start < min {
+            min - start
+        } else if end > max {
+            max - end
+        } else {
+            return;
+        };
+        self.translate(delta);
     }
-}
 
-pub struct Plot<'mem> {
-    title: Option<String>,
-    show_cursor_pos: bool,
-    memory: &'mem mut PlotMemory,
-    size: Vec2,
-    x_axis: Axis,
-    y_axis: Axis,
-}
+    /// Zoom, unless that would take the extent outside of `extent_limits`. Stopping instead of
+    /// clamping keeps the point under the cursor fixed.
+    fn zoom_limited(&mut self, amount: f64, center: f64, extent_limits: RangeInclusive<f64>) {
+        let mut zoomed = *self;
+        zoomed.zoom(amount, center);
+        let extent = zoomed.extent();
+        let too_small = amount < 0. && extent < *extent_limits.start();
+        let too_large = amount > 0. && extent > *extent_limits.end();
+        if extent.is_finite() && extent > 0. && !too_small && !too_large {
+            *self = zoomed;
+        }
+    }
 
-pub(crate) struct PlotMemory {
-    last_drag_pos: Option<Pos2>,
-    x_axis_range: AxisRange,
-    y_axis_range: AxisRange,
-}
+    /// Position in scaled space for a pixel coordinate.
+    fn pixel_to_scaled(&self, pixel_range: RangeInclusive<f32>, pixel: f32, flip: bool) -> f64 {
+        let t = remap(pixel as f64, to_f64(pixel_range), 0.0..=1.0);
+        let t = if flip { 1. - t } else { t };
+        lerp(self.scaled_range(), t)
+    }
 
-impl Default for PlotMemory {
-    fn default() -> Self {
-        Self {
-            last_drag_pos: None,
-            x_axis_range: AxisRange::new((-10.)..=10.),
-            y_axis_range: AxisRange::new((-10.)..=10.),
+    fn pixel_to_axis(&self, pixel_range: RangeInclusive<f32>, pixel: f32, flip: bool) -> f64 {
+        self.unscale(self.pixel_to_scaled(pixel_range, pixel, flip))
+    }
+
+    /// The math is done in `f64`, only the resulting pixel coordinate is `f32`.
+    fn axis_to_pixel(&self, pixel_range: RangeInclusive<f32>, axis_pos: f64, flip: bool) -> f32 {
+        let t = remap(self.scale(axis_pos), self.scaled_range(), 0.0..=1.0);
+        let t = if flip { 1. - t } else { t };
+        lerp(to_f64(pixel_range), t) as f32
+    }
+
+    /// The distance between ticks of a linear axis.
+    pub(crate) fn increment(&self, spacing: TickSpacing) -> f64 {
+        match spacing {
+            TickSpacing::Fixed(increment) => increment,
+            TickSpacing::Auto { target_count } => tick_increment(self.extent(), target_count),
+            TickSpacing::None => tick_increment(self.extent(), 5),
+        }
+    }
+
+    pub(crate) fn magnitude(&self) -> f64 {
+        self.start.abs().max(self.end.abs())
+    }
+
+    /// Format a value with the same precision as the tick labels of this range.
+    fn format_value(&self, value: f64, spacing: TickSpacing) -> String {
+        if self.is_log() {
+            // Two significant digits, like the minor ticks within a decade.
+            return format_significant(value, 2);
         }
+        format_tick(value, self.increment(spacing), self.magnitude())
     }
 }
 
-#[derive(Default)]
-pub struct PlotCtx {
-    pub(crate) memory: HashMap<Id, PlotMemory>,
+/// Maps plot coordinates to screen coordinates within the plot area, and back.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScreenTransform {
+    /// The plot area, in screen coordinates.
+    frame: Rect,
+    x_range: AxisRange,
+    y_range: AxisRange,
+    /// Screen coordinate per scaled value along x and y, so positions take a multiply-add.
+    scale: [f64; 2],
+    /// Screen coordinate of a scaled value of zero along x and y.
+    offset: [f64; 2],
 }
 
-impl PlotCtx {
-    pub fn plot(&mut self, label: impl Into<String>) -> Plot {
-        let id = Id::new(label.into());
-        let memory = self.memory.entry(id).or_default();
-        Plot::new_with_memory(memory)
+impl ScreenTransform {
+    /// Maps `bounds` onto `frame` with linear axes.
+    pub fn new(frame: Rect, bounds: PlotBounds) -> Self {
+        Self::from_axes(
+            frame,
+            AxisRange::new(bounds.x_range()),
+            AxisRange::new(bounds.y_range()),
+        )
     }
-}
 
-impl<'mem> Plot<'mem> {
-    fn new_with_memory(memory: &'mem mut PlotMemory) -> Self {
+    fn from_axes(frame: Rect, x_range: AxisRange, y_range: AxisRange) -> Self {
+        let (x, y) = (x_range.scaled_range(), y_range.scaled_range());
+        // The y axis points up, screen coordinates grow downwards.
+        let scale = [
+            frame.width() as f64 / (x.end() - x.start()),
+            -frame.height() as f64 / (y.end() - y.start()),
+        ];
+        let offset = [
+            frame.left() as f64 - x.start() * scale[0],
+            frame.bottom() as f64 - y.start() * scale[1],
+        ];
         Self {
-            title: None,
-            show_cursor_pos: true,
-            memory,
-            size: vec2(100., 100.),
-            x_axis: Axis::default(),
-            y_axis: Axis::default(),
+            frame,
+            x_range,
+            y_range,
+            scale,
+            offset,
         }
     }
 
-    pub fn title(mut self, title: impl Into<String>) -> Self {
-        self.title = Some(title.into());
-        self
+    /// The plot area, in screen coordinates.
+    pub fn frame(&self) -> &Rect {
+        &self.frame
     }
 
-    pub fn size(mut self, size: Vec2) -> Self {
-        self.size = size;
-        self
+    /// The visible part of the plot, in plot coordinates.
+    pub fn bounds(&self) -> PlotBounds {
+        PlotBounds::from_x_y_ranges(
+            self.x_range.start..=self.x_range.end,
+            self.y_range.start..=self.y_range.end,
+        )
     }
 
-    pub fn x_axis_range(mut self, range: RangeInclusive<f32>) -> Self {
-        self.x_axis.range = AxisRange::new(range);
-        self
+    /// Screen position of a value.
+    pub fn position(&self, value: &Value) -> Pos2 {
+        let x = self.x_range.scale(value.x) * self.scale[0] + self.offset[0];
+        let y = self.y_range.scale(value.y) * self.scale[1] + self.offset[1];
+        pos2(x as f32, y as f32)
     }
 
-    pub fn y_axis_range(mut self, range: RangeInclusive<f32>) -> Self {
-        self.y_axis.range = AxisRange::new(range);
-        self
+    /// Screen positions of `values`, in the same order. With the `rayon` feature, long slices
+    /// are transformed in parallel.
+    pub fn positions(&self, values: &[Value]) -> Vec<Pos2> {
+        let mut positions = Vec::with_capacity(values.len());
+        self.positions_into(values, &mut positions);
+        positions
     }
 
-    /// Show the cursor position in the bottol left corner.
-    pub fn show_cursor_position(mut self, on: bool) -> Self {
-        self.show_cursor_pos = on;
-        self
+    /// Like `positions`, but replaces the contents of `out` to reuse its allocation.
+    pub fn positions_into(&self, values: &[Value], out: &mut Vec<Pos2>) {
+        #[cfg(feature = "rayon")]
+        if values.len() >= PARALLEL_MIN_POINTS {
+            use rayon::prelude::*;
+            return values
+                .par_iter()
+                .map(|v| self.position(v))
+                .collect_into_vec(out);
+        }
+        out.clear();
+        out.extend(values.iter().map(|v| self.position(v)));
     }
 
-    /// Draw the plot. Takes a closure where contents can be added to the plot.
-    pub fn show<R>(self, ui: &mut Ui, add_contents: impl FnOnce(&mut PlotUi) -> R) -> Response {
-        let Self {
-            show_cursor_pos,
-            memory,
-            title,
-            size,
-            mut x_axis,
-            mut y_axis,
-        } = self;
+    /// The value at a screen position.
+    pub fn value(&self, pos: &Pos2) -> Value {
+        Value::new(
+            self.x_range
+                .pixel_to_axis(self.frame.x_range(), pos.x, false),
+            self.y_range
+                .pixel_to_axis(self.frame.y_range(), pos.y, true),
+        )
+    }
 
-        Resize::default().default_size(size).show(ui, |ui| {
-            let PlotMemory {
-                last_drag_pos,
-                x_axis_range,
-                y_axis_range,
-            } = memory;
+    /// The screen rectangle covered by `bounds`.
+    pub fn rect(&self, bounds: &PlotBounds) -> Rect {
+        Rect::from_two_pos(self.position(&bounds.min), self.position(&bounds.max))
+    }
 
-            x_axis.range = *x_axis_range;
-            y_axis.range = *y_axis_range;
+    /// The size of one pixel in plot units along x and y. The y component is negative because
+    /// screen coordinates grow downwards. For a logarithmic axis it is measured in decades.
+    pub fn dvalue_dpos(&self) -> [f64; 2] {
+        let (x, y) = (self.x_range.scaled_range(), self.y_range.scaled_range());
+        [
+            (x.end() - x.start()) / self.frame.width() as f64,
+            -(y.end() - y.start()) / self.frame.height() as f64,
+        ]
+    }
+}
 
-            let (response, mut painter) =
-                ui.allocate_painter(ui.available_size_before_wrap_finite(), Sense::drag());
+/// Below this many points, transforming them in parallel isn't worth the overhead.
+#[cfg(feature = "rayon")]
+pub(crate) const PARALLEL_MIN_POINTS: usize = 16_384;
 
-            // Plotting area
-            let left_margin = 40.;
-            let right_margin = 10.;
-            let mut bottom_margin = 40.;
-            let mut top_margin = 10.;
-            if title.is_some() {
-                top_margin += 10.
-            }
-            if !x_axis.label.is_empty() {
-                bottom_margin += 10.
-            }
+/// A pixel range, to be combined with values in plot coordinates.
+fn to_f64(range: RangeInclusive<f32>) -> RangeInclusive<f64> {
+    *range.start() as f64..=*range.end() as f64
+}
 
-            // The full plot rectangle, including title, axes, and their labels.
-            let full_rect = response.rect;
+/// How the ticks on an axis are spaced.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TickSpacing {
+    /// Choose a round increment that results in roughly `target_count` ticks.
+    Auto { target_count: usize },
+    /// Place ticks at multiples of the given increment.
+    Fixed(f64),
+    /// Don't show ticks on this axis.
+    None,
+}
 
-            // The rectangle that contains the plot items.
-            let painter_rect = Rect::from_min_max(
-                full_rect.min + vec2(left_margin, top_margin),
-                full_rect.max - vec2(right_margin, bottom_margin),
-            );
-            painter.rect(
-                painter_rect,
-                0.,
-                Color32::from_gray(10),
-                Stroke::new(1.0, Color32::from_white_alpha(150)),
-            );
+impl Default for TickSpacing {
+    fn default() -> Self {
+        Self::Auto { target_count: 5 }
+    }
+}
 
-            if let Some(title) = title {
-                painter.text(
-                    painter_rect.center_top() - vec2(0., 2.),
-                    Align2::CENTER_BOTTOM,
-                    title,
-                    TextStyle::Monospace,
-                    Color32::WHITE,
-                );
-            }
+/// How the tick labels of an axis are formatted.
+#[derive(Default)]
+pub enum TickFormat {
+    /// Decimal numbers with just enough digits to tell the ticks apart.
+    #[default]
+    Auto,
+    /// Multiples of π like "π/2" or "3π/4". The ticks are also placed at fractions of π.
+    Pi,
+    /// Ratios shown as percentages, e.g. 0.25 as "25%". The ticks are placed at steps like 5%,
+    /// 10% or 25%.
+    Percent { decimals: usize },
+    /// Format every major tick with a custom function.
+    Custom(Box<dyn Fn(f64) -> String>),
+}
 
-            if !x_axis.label.is_empty() {
-                painter.text(
-                    painter_rect.center_bottom() + vec2(0., 25.),
-                    Align2::CENTER_TOP,
-                    x_axis.label.clone(),
-                    TextStyle::Monospace,
-                    Color32::WHITE,
-                );
+/// How the decades of a logarithmic axis are labeled.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum LogLabels {
+    /// Plain numbers like "1000" for small exponents, "1e5" otherwise.
+    #[default]
+    Plain,
+    /// Powers of ten with a superscript exponent, like "10³".
+    Superscript,
+    /// Exponent notation, like "1e3".
+    Exponent,
+}
+
+impl LogLabels {
+    pub(crate) fn format(self, decade: i32) -> String {
+        match self {
+            Self::Plain if (-3..=4).contains(&decade) => format!("{}", 10f64.powi(decade)),
+            Self::Plain | Self::Exponent => format!("1e{}", decade),
+            Self::Superscript => {
+                let exponent: String = decade
+                    .to_string()
+                    .chars()
+                    .map(|c| match c {
+                        '-' => '⁻',
+                        '1' => '¹',
+                        '2' => '²',
+                        '3' => '³',
+                        d => std::char::from_u32(0x2070 + d.to_digit(10).unwrap_or(0)).unwrap_or(d),
+                    })
+                    .collect();
+                format!("10{}", exponent)
             }
+        }
+    }
+}
 
-            // TODO: Y-axis label.
+/// How the title is drawn. The alignment is relative to the plot area.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TitleStyle {
+    /// The text color. Follows the visuals if not set.
+    pub color: Option<Color32>,
+    pub text_style: TextStyle,
+    pub align: Align,
+}
 
-            // Adjust the axes so that the aspect ratio is equal.
-            let painter_height = painter_rect.height();
-            let painter_width = painter_rect.width();
-            let plot_width = x_axis.range.extent();
-            let plot_height = y_axis.range.extent();
-            let max_half_extent = plot_width.max(plot_height) / 2.;
-            let painter_ratio = painter_height / painter_width;
-            if painter_ratio > 1. {
-                let x_center = x_axis.range.middle();
-                x_axis.range.start = x_center - max_half_extent / painter_ratio;
-                x_axis.range.end = x_center + max_half_extent / painter_ratio;
-            } else {
-                let y_center = y_axis.range.middle();
-                y_axis.range.start = y_center - max_half_extent * painter_ratio;
-                y_axis.range.end = y_center + max_half_extent * painter_ratio;
-            }
+impl Default for TitleStyle {
+    fn default() -> Self {
+        Self {
+            color: None,
+            text_style: TextStyle::Monospace,
+            align: Align::Center,
+        }
+    }
+}
 
-            // Dragging
-            let new_drag_pos = response.interact_pointer_pos();
-            if let Some(pos) = new_drag_pos {
-                let x_tf = x_axis
-                    .range
-                    .pixel_to_axis(painter_rect.x_range(), pos.x, false);
-                let y_tf = y_axis
-                    .range
-                    .pixel_to_axis(painter_rect.y_range(), pos.y, true);
-                let pos_tf = Pos2::new(x_tf, y_tf);
-
-                if let Some(last_pos) = last_drag_pos {
-                    ui.output().cursor_icon = CursorIcon::Grabbing;
-                    let x_tf =
-                        x_axis
-                            .range
-                            .pixel_to_axis(painter_rect.x_range(), last_pos.x, false);
-                    let y_tf = y_axis
-                        .range
-                        .pixel_to_axis(painter_rect.y_range(), last_pos.y, true);
-                    let last_pos_tf = Pos2::new(x_tf, y_tf);
+/// A side of the plot area.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Side {
+    Left,
+    Right,
+    Both,
+}
 
-                    let delta = last_pos_tf - pos_tf;
-                    x_axis.range.translate(delta.x);
-                    y_axis.range.translate(delta.y);
-                }
-                *last_drag_pos = Some(pos);
-            } else {
-                *last_drag_pos = None;
-            }
+/// The list of named items in the top right corner of the plot.
+struct Legend<'a> {
+    entries: &'a [(String, Color32)],
+    hidden_items: &'a HashSet<String>,
+    text_style: TextStyle,
+    text_color: Color32,
+    background_color: Color32,
+    frame_stroke: Stroke,
+}
 
-            // Zooming
-            let scrolled = ui.input().scroll_delta.y.clamp(-10., 10.);
-            if let Some(mouse_pos) = ui
-                .input()
-                .pointer
-                .interact_pos()
-                .filter(|pos| painter_rect.contains(*pos))
-            {
-                if scrolled != 0. {
-                    let left_distance = (mouse_pos.x - painter_rect.left()) / painter_rect.width();
-                    let bottom_distance =
-                        (painter_rect.bottom() - mouse_pos.y) / painter_rect.height();
-                    let zoom_factor = -0.01 * scrolled;
-                    x_axis.range.zoom(zoom_factor, left_distance);
-                    y_axis.range.zoom(zoom_factor, bottom_distance);
-                }
-            }
+/// Space between the legend and the edges of the plot area.
+const LEGEND_MARGIN: f32 = 5.;
 
-            let plot_to_screen = |pos: &Pos2| -> Pos2 {
-                Self::plot_to_pixels(pos, &x_axis.range, &y_axis.range, &painter_rect)
-            };
-            let screen_to_plot = |pos: &Pos2| -> Pos2 {
-                Self::pixels_to_plot(pos, &x_axis.range, &y_axis.range, &painter_rect)
-            };
+/// Distance in pixels from a corner within which a released legend snaps to it.
+const LEGEND_SNAP_DISTANCE: f32 = 20.;
 
-            // Ticks and tick labels
-            let ticks_on_smaller_axis = 5; // The lower limit of ticks on the smaller axis.
-            let smaller_axis_size = x_axis.range.extent().min(y_axis.range.extent());
-            let rough_increment = smaller_axis_size / ticks_on_smaller_axis as f32;
-            let increment = emath::smart_aim::best_in_range_f64(
-                (rough_increment * 0.5) as f64,
-                (rough_increment * 1.5) as f64,
-            ) as f32;
-
-            // X-Axis ticks
-            let mut i_start = (x_axis.range.start / increment) as i32;
-            if i_start >= 0 {
-                i_start += 1;
-            }
-            loop {
-                let tick_pos_x = i_start as f32 * increment;
-                if tick_pos_x > x_axis.range.end {
-                    break;
-                }
-                let x_tick = plot_to_screen(&pos2(tick_pos_x, y_axis.range.start));
-                painter.line_segment(
-                    [x_tick, x_tick - 5. * Vec2::Y],
-                    Stroke::new(1.0, Color32::WHITE),
+impl<'a> Legend<'a> {
+    fn size(&self, painter: &Painter) -> Vec2 {
+        let row_height = painter.fonts().row_height(self.text_style);
+        let padding = 4.;
+        let text_width = self
+            .entries
+            .iter()
+            .map(|(name, _)| {
+                painter
+                    .layout_no_wrap(name.clone(), self.text_style, self.text_color)
+                    .size()
+                    .x
+            })
+            .fold(0., f32::max);
+        vec2(
+            3. * padding + row_height + text_width,
+            2. * padding + self.entries.len() as f32 * row_height,
+        )
+    }
+
+    /// Paint the legend into `rect`. Returns the name of the entry under the pointer.
+    fn paint(&self, painter: &Painter, rect: Rect, pointer: Option<Pos2>) -> Option<String> {
+        let row_height = painter.fonts().row_height(self.text_style);
+        let padding = 4.;
+        painter.rect(rect, 2., self.background_color, self.frame_stroke);
+
+        let mut hovered = None;
+        self.entries
+            .iter()
+            .enumerate()
+            .for_each(|(i, (name, color))| {
+                let row = Rect::from_min_size(
+                    rect.min + vec2(padding, padding + i as f32 * row_height),
+                    vec2(rect.width() - 2. * padding, row_height),
                 );
-                painter.line_segment(
-                    [x_tick, x_tick - painter_rect.height() * Vec2::Y],
-                    Stroke::new(0.5, Color32::from_white_alpha(5)),
+                let hidden = self.hidden_items.contains(name);
+                let (color, text_color) = if hidden {
+                    let grey = self.text_color.linear_multiply(0.3);
+                    (grey, grey)
+                } else {
+                    (*color, self.text_color)
+                };
+                let swatch = Rect::from_center_size(
+                    row.left_center() + vec2(row_height / 2., 0.),
+                    Vec2::splat(0.6 * row_height),
                 );
-                painter.text(
-                    x_tick + 15. * Vec2::Y,
-                    Align2::CENTER_CENTER,
-                    format!("{:.1}", tick_pos_x),
-                    TextStyle::Monospace,
-                    Color32::WHITE,
+                painter.rect_filled(swatch, 1., color);
+                let galley = painter.layout_no_wrap(name.clone(), self.text_style, text_color);
+                painter.galley(row.min + vec2(row_height + padding, 0.), galley);
+                if pointer.filter(|pointer| row.contains(*pointer)).is_some() {
+                    hovered = Some(name.clone());
+                }
+            });
+        hovered
+    }
+}
+
+/// Place a legend of the given size. The position is the corner of the plot area the legend is
+/// attached to and the offset of the legend's corresponding corner from it.
+fn place_legend(position: (Align2, Vec2), size: Vec2, painter_rect: Rect) -> Rect {
+    let (align, offset) = position;
+    let factors = vec2(align.x().to_factor(), align.y().to_factor());
+    let corner = painter_rect.min + painter_rect.size() * factors + offset;
+    let rect = align.anchor_rect(Rect::from_min_size(corner, size));
+    // Stay inside the plot area if it got smaller.
+    let rect = rect.translate((painter_rect.max - rect.max).min(Vec2::ZERO));
+    rect.translate((painter_rect.min - rect.min).max(Vec2::ZERO))
+}
+
+/// The position of a legend that was dropped at `rect`, attached to a corner if it is close to
+/// one and to the top left corner otherwise.
+fn snap_legend(rect: Rect, painter_rect: Rect) -> (Align2, Vec2) {
+    let inner = painter_rect.shrink(LEGEND_MARGIN);
+    let corners = vec![
+        Align2::LEFT_TOP,
+        Align2::RIGHT_TOP,
+        Align2::LEFT_BOTTOM,
+        Align2::RIGHT_BOTTOM,
+    ];
+    let corner_of = |rect: Rect, align: Align2| {
+        rect.min + rect.size() * vec2(align.x().to_factor(), align.y().to_factor())
+    };
+    corners
+        .into_iter()
+        .find(|align| {
+            corner_of(rect, *align).distance(corner_of(inner, *align)) < LEGEND_SNAP_DISTANCE
+        })
+        .map(|align| {
+            (
+                align,
+                corner_of(inner, align) - corner_of(painter_rect, align),
+            )
+        })
+        .unwrap_or((Align2::LEFT_TOP, rect.min - painter_rect.min))
+}
+
+/// Whether tick marks point into the plot area or away from it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TickDirection {
+    Inward,
+    Outward,
+}
+
+/// How the tick marks along the axes are drawn. Minor ticks are drawn shorter.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TickMarks {
+    pub length: f32,
+    pub direction: TickDirection,
+    /// Draw the marks at all, or only the labels.
+    pub visible: bool,
+}
+
+impl Default for TickMarks {
+    fn default() -> Self {
+        Self {
+            length: 5.,
+            direction: TickDirection::Inward,
+            visible: true,
+        }
+    }
+}
+
+impl TickMarks {
+    /// Paint a tick mark on the edge of the plot area. `inward` points into the plot area.
+    fn paint(
+        &self,
+        painter: &mut PlotPainter,
+        pos: Pos2,
+        inward: Vec2,
+        major: bool,
+        color: Color32,
+    ) {
+        if !self.visible {
+            return;
+        }
+        let length = if major {
+            self.length
+        } else {
+            0.6 * self.length
+        };
+        let direction = match self.direction {
+            TickDirection::Inward => inward,
+            TickDirection::Outward => -inward,
+        };
+        painter.line_segment([pos, pos + length * direction], Stroke::new(1.0, color));
+    }
+
+    /// How far the marks reach out of the plot area, which the labels have to clear.
+    fn outward_length(&self) -> f32 {
+        if self.visible && self.direction == TickDirection::Outward {
+            self.length
+        } else {
+            0.
+        }
+    }
+}
+
+/// A pointer button together with the modifiers that have to be held while dragging it.
+/// Additional modifiers may be held as well.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DragBinding {
+    pub button: PointerButton,
+    pub modifiers: Modifiers,
+}
+
+impl DragBinding {
+    pub fn new(button: PointerButton) -> Self {
+        Self {
+            button,
+            modifiers: Modifiers::default(),
+        }
+    }
+
+    pub fn modifiers(mut self, modifiers: Modifiers) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
+    fn active(&self, input: &InputState) -> bool {
+        input.pointer.button_down(self.button) && modifiers_held(self.modifiers, input.modifiers)
+    }
+}
+
+/// Whether all of the `required` modifiers are held.
+fn modifiers_held(required: Modifiers, modifiers: Modifiers) -> bool {
+    (modifiers.alt || !required.alt)
+        && (modifiers.ctrl || !required.ctrl)
+        && (modifiers.shift || !required.shift)
+        && (modifiers.command || !required.command)
+}
+
+/// Which drags start which gesture. If several match, measuring goes first, then selecting,
+/// box zooming and panning.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InteractionSettings {
+    pub pan: Vec<DragBinding>,
+    pub box_zoom: Vec<DragBinding>,
+    /// Measures the distance and slope between two points.
+    pub measure: Vec<DragBinding>,
+    /// See `Plot::rect_selection`.
+    pub select: Vec<DragBinding>,
+}
+
+impl Default for InteractionSettings {
+    fn default() -> Self {
+        let with =
+            |modifiers: Modifiers| DragBinding::new(PointerButton::Primary).modifiers(modifiers);
+        Self {
+            pan: vec![
+                DragBinding::new(PointerButton::Primary),
+                DragBinding::new(PointerButton::Middle),
+            ],
+            box_zoom: vec![
+                DragBinding::new(PointerButton::Secondary),
+                with(Modifiers {
+                    shift: true,
+                    ..Default::default()
+                }),
+            ],
+            measure: vec![with(Modifiers {
+                alt: true,
+                ..Default::default()
+            })],
+            select: vec![with(Modifiers {
+                ctrl: true,
+                ..Default::default()
+            })],
+        }
+    }
+}
+
+impl InteractionSettings {
+    /// A binding that is assigned to more than one gesture.
+    fn conflict(&self) -> Option<DragBinding> {
+        let gestures = [&self.pan, &self.box_zoom, &self.measure, &self.select];
+        gestures.iter().enumerate().find_map(|(i, bindings)| {
+            bindings.iter().copied().find(|binding| {
+                gestures[i + 1..]
+                    .iter()
+                    .any(|other| other.contains(binding))
+            })
+        })
+    }
+}
+
+/// Space between tick marks and their labels, and between tick labels and axis labels.
+const LABEL_GAP: f32 = 5.;
+
+/// The widest tick label, used to size the margins.
+fn max_label_width(painter: &Painter, ticks: &[Tick], text_style: TextStyle) -> f32 {
+    ticks
+        .iter()
+        .filter_map(|tick| tick.label.as_ref())
+        .map(|label| {
+            painter
+                .layout_no_wrap(label.clone(), text_style, Color32::WHITE)
+                .size()
+                .x
+        })
+        .fold(0., f32::max)
+}
+
+/// The smallest step `k` such that showing every `k`-th of the centered labels, given as
+/// `(center, width)`, leaves at least `min_gap` between neighbors.
+fn label_stride(label_extents: &[(f32, f32)], min_gap: f32) -> usize {
+    (1..label_extents.len().max(1))
+        .find(|&stride| {
+            label_extents
+                .iter()
+                .step_by(stride)
+                .zip(label_extents.iter().step_by(stride).skip(1))
+                .all(|((x_a, w_a), (x_b, w_b))| (x_b - x_a).abs() >= (w_a + w_b) / 2. + min_gap)
+        })
+        .unwrap_or_else(|| label_extents.len().max(1))
+}
+
+/// Paint a label rotated counter-clockwise so it reads upwards, centered along `y_range`.
+/// Labels longer than the range are shortened with an ellipsis.
+fn paint_vertical_label(
+    painter: &Painter,
+    label: &str,
+    left: f32,
+    y_range: RangeInclusive<f32>,
+    text_style: TextStyle,
+    color: Color32,
+) {
+    let max_length = y_range.end() - y_range.start();
+    let mut label = label.to_string();
+    let mut galley = painter.layout_no_wrap(label.clone(), text_style, color);
+    while galley.size().x > max_length && label.pop().is_some() {
+        galley = painter.layout_no_wrap(format!("{}…", label), text_style, color);
+    }
+    // The text is rotated around its top left corner.
+    let center_y = (y_range.start() + y_range.end()) / 2.;
+    let pos = pos2(left, center_y + galley.size().x / 2.);
+    let mut text = TextShape::new(pos, galley);
+    text.angle = -std::f32::consts::FRAC_PI_2;
+    painter.add(Shape::Text(text));
+}
+
+/// An additional x axis along the top edge of the plot.
+pub enum TopAxis {
+    /// Repeat the tick marks of the bottom axis, without labels.
+    Mirror,
+    /// Show a different scale, e.g. frequency on top of wavelength, or the same values in
+    /// another unit with `Plot::x_axis_twin`. `to_top` maps bottom axis values to the top scale
+    /// and must be monotonic over the visible range. Without the inverse `from_top`, the top
+    /// ticks are found by bisection. Labels use the default formatting if no `formatter` is
+    /// given.
+    Transformed {
+        to_top: Box<dyn Fn(f64) -> f64>,
+        from_top: Option<Box<dyn Fn(f64) -> f64>>,
+        formatter: Option<Box<dyn Fn(f64) -> String>>,
+    },
+}
+
+/// Ticks at nice values of the top scale, as their position on the bottom axis and label.
+fn top_ticks(
+    range: &AxisRange,
+    pixels: f32,
+    to_top: &dyn Fn(f64) -> f64,
+    from_top: &dyn Fn(f64) -> Option<f64>,
+    formatter: Option<&dyn Fn(f64) -> String>,
+) -> Vec<(f64, String)> {
+    let (a, b) = (to_top(range.start), to_top(range.end));
+    if !a.is_finite() || !b.is_finite() || a == b {
+        return Vec::new();
+    }
+    let format = TickFormat::Auto;
+    let config = TickConfig {
+        spacing: TickSpacing::default(),
+        format: &format,
+        time: None,
+        si_unit: None,
+        log_labels: LogLabels::default(),
+        minor_ticks: false,
+    };
+    generate_ticks(&AxisRange::new(a.min(b)..=a.max(b)), pixels, &config)
+        .into_iter()
+        .filter_map(|tick| {
+            let x = from_top(tick.value)?;
+            let label = match formatter {
+                Some(formatter) => formatter(tick.value),
+                None => tick.label.unwrap_or_default(),
+            };
+            Some((x, label))
+        })
+        .collect()
+}
+
+/// Find the bottom axis value that `to_top` maps to `target`, by bisection over `range`.
+fn invert_monotonic(to_top: &dyn Fn(f64) -> f64, target: f64, range: &AxisRange) -> Option<f64> {
+    let (mut lo, mut hi) = (range.scale(range.start), range.scale(range.end));
+    let increasing = to_top(range.unscale(hi)) >= to_top(range.unscale(lo));
+    for _ in 0..50 {
+        let mid = (lo + hi) / 2.;
+        let value = to_top(range.unscale(mid));
+        if !value.is_finite() {
+            return None;
+        }
+        if (value < target) == increasing {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    Some(range.unscale((lo + hi) / 2.))
+}
+
+/// Grow `rect` around its center to the aspect ratio of `target`, so zooming into it keeps the
+/// aspect ratio of the plot.
+fn expand_to_aspect(rect: Rect, target: Rect) -> Rect {
+    let scale = (rect.width() / target.width()).max(rect.height() / target.height());
+    Rect::from_center_size(rect.center(), target.size() * scale)
+}
+
+/// Expand the extent of some data by `fraction` of it and by `abs` on each side, so the
+/// outermost points don't sit on the frame. A single value gets a small window around it.
+fn padded_range(min: f64, max: f64, fraction: f64, abs: f64) -> RangeInclusive<f64> {
+    if max - min <= 0. {
+        let half = (0.1 * min.abs()).max(0.5) + abs;
+        return (min - half)..=(max + half);
+    }
+    let padding = (max - min) * fraction + abs;
+    (min - padding)..=(max + padding)
+}
+
+/// Minimum space around the plotting area, in points. The margins grow beyond these to fit the
+/// tick labels and axis labels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Margins {
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+}
+
+impl Default for Margins {
+    fn default() -> Self {
+        Self {
+            left: 10.,
+            right: 10.,
+            top: 10.,
+            bottom: 10.,
+        }
+    }
+}
+
+pub struct Axis {
+    label: String,
+    range: AxisRange,
+    ticks: TickSpacing,
+    tick_values: Option<(Vec<f64>, Option<Vec<String>>)>,
+    unit: String,
+    si_prefix: bool,
+    log_labels: LogLabels,
+    format: TickFormat,
+}
+
+impl Default for Axis {
+    fn default() -> Self {
+        Self {
+            label: "".to_string(),
+            range: AxisRange::new((-10.)..=10.),
+            ticks: TickSpacing::default(),
+            tick_values: None,
+            unit: "".to_string(),
+            si_prefix: false,
+            log_labels: LogLabels::default(),
+            format: TickFormat::default(),
+        }
+    }
+}
+
+impl Axis {
+    pub fn new(range: RangeInclusive<f64>) -> Self {
+        Self {
+            range: AxisRange::new(range),
+            ..Default::default()
+        }
+    }
+
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = label.into();
+        self
+    }
+
+    /// The unit of the values on this axis. It is shown with the axis label and cursor position.
+    pub fn unit(mut self, unit: impl Into<String>) -> Self {
+        self.unit = unit.into();
+        self
+    }
+
+    /// Show tick labels with SI prefixes and the unit, e.g. "12 µs" instead of "0.000012".
+    pub fn si_prefix(mut self, on: bool) -> Self {
+        self.si_prefix = on;
+        self
+    }
+
+    /// How the tick labels are formatted.
+    pub fn format(mut self, format: TickFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    fn uses_si_prefix(&self) -> bool {
+        self.si_prefix && !self.unit.is_empty() && !self.range.is_log()
+    }
+
+    /// The axis label including the unit, unless the unit is already part of the tick labels.
+    fn full_label(&self) -> String {
+        if self.unit.is_empty() || self.uses_si_prefix() {
+            self.label.clone()
+        } else if self.label.is_empty() {
+            format!("[{}]", self.unit)
+        } else {
+            format!("{} [{}]", self.label, self.unit)
+        }
+    }
+
+    /// Format a value at the resolution of the ticks, with the unit.
+    fn format_value(&self, value: f64) -> String {
+        match &self.format {
+            TickFormat::Auto => {}
+            TickFormat::Pi => {
+                let pi = std::f64::consts::PI;
+                let increment = match self.ticks {
+                    TickSpacing::Fixed(increment) => increment,
+                    _ => pi_increment(self.range.extent(), 5),
+                };
+                let value = format_tick(value / pi, increment / pi, self.range.magnitude() / pi);
+                return format!("{}π", value);
+            }
+            TickFormat::Percent { decimals } => return format_percent(value, *decimals),
+            TickFormat::Custom(formatter) => return formatter(value),
+        }
+        if self.uses_si_prefix() {
+            let increment = self.range.increment(self.ticks);
+            format_si(value, increment, self.range.magnitude(), &self.unit)
+        } else if self.unit.is_empty() {
+            self.range.format_value(value, self.ticks)
+        } else {
+            format!(
+                "{} {}",
+                self.range.format_value(value, self.ticks),
+                self.unit
+            )
+        }
+    }
+
+    /// Label one of the explicit tick values with just enough decimals to tell it apart from
+    /// the closest other one. On a logarithmic axis, powers of ten are labeled like the
+    /// generated decade ticks.
+    fn format_tick_value(&self, values: &[f64], value: f64) -> String {
+        if self.range.is_log() {
+            let decade = value.log10().round();
+            if (value.log10() - decade).abs() < 1e-9 {
+                return self.log_labels.format(decade as i32);
+            }
+            return self.range.format_value(value, self.ticks);
+        }
+        let gap = values
+            .iter()
+            .map(|other| (other - value).abs())
+            .filter(|gap| *gap > 0.)
+            .fold(f64::INFINITY, f64::min);
+        if gap.is_finite() {
+            format_tick(value, gap, self.range.magnitude())
+        } else {
+            self.range.format_value(value, self.ticks)
+        }
+    }
+
+    /// The ticks that are visible in the current range, on an axis `pixels` long.
+    fn ticks(&self, pixels: f32, log_minor_ticks: bool, time: Option<TimeFormat>) -> Vec<Tick> {
+        if let Some((values, labels)) = &self.tick_values {
+            let range = &self.range;
+            values
+                .iter()
+                .enumerate()
+                .filter(|(_, value)| **value >= range.start && **value <= range.end)
+                .map(|(i, value)| {
+                    let label = labels
+                        .as_ref()
+                        .and_then(|labels| labels.get(i).cloned())
+                        .unwrap_or_else(|| self.format_tick_value(values, *value));
+                    Tick::major(*value, label)
+                })
+                .collect()
+        } else {
+            let config = TickConfig {
+                spacing: self.ticks,
+                format: &self.format,
+                time,
+                si_unit: Some(self.unit.as_str()).filter(|_| self.uses_si_prefix()),
+                log_labels: self.log_labels,
+                minor_ticks: log_minor_ticks,
+            };
+            generate_ticks(&self.range, pixels, &config)
+        }
+    }
+}
+
+/// Explicit tick labels need to be parallel to the tick values.
+fn debug_assert_labels_match(values: &[f64], labels: &Option<Vec<String>>) {
+    if let Some(labels) = labels {
+        debug_assert_eq!(
+            labels.len(),
+            values.len(),
+            "expected a tick label for each of the tick values"
+        );
+    }
+}
+
+/// Adds custom entries to the context menu, given the clicked position in plot coordinates.
+type ContextMenuEntries<'a> = Box<dyn FnOnce(&mut Ui, Value) + 'a>;
+
+/// Items kept by the plot, see `Plot::retain`.
+type BoxedItems = Vec<Box<dyn PlotItem>>;
+
+/// Updates the items kept by the plot.
+type RetainItems<'a> = Box<dyn FnOnce(&mut BoxedItems) + 'a>;
+
+/// Formats the text copied to the clipboard.
+type CopyFormatter = Box<dyn Fn(&CopiedValue) -> String>;
+
+pub struct Plot<'mem> {
+    title: Option<String>,
+    show_cursor_pos: bool,
+    cursor_formatter: Option<Box<dyn Fn(Value) -> String>>,
+    copy_formatter: Option<CopyFormatter>,
+    cursor_readout_corner: Align2,
+    memory: &'mem mut PlotMemory,
+    link_groups: &'mem mut LinkGroups,
+    link_x: Option<String>,
+    link_y: Option<String>,
+    size: Vec2,
+    x_axis: Axis,
+    y_axis: Axis,
+    aspect_ratio: Option<f32>,
+    log_minor_ticks: bool,
+    x_time: Option<TimeFormat>,
+    secondary_y_axis: Option<Axis>,
+    top_x_axis: Option<TopAxis>,
+    margins: Margins,
+    min_zoom_extent: Vec2,
+    max_zoom_extent: Vec2,
+    bounds: Option<PlotBounds>,
+    zoom_to: Option<PlotBounds>,
+    lock_x: bool,
+    lock_y: bool,
+    allow_keyboard: bool,
+    allow_scale_toggle: bool,
+    allow_drag: bool,
+    interaction: InteractionSettings,
+    allow_zoom: bool,
+    zoom_speed: f32,
+    invert_zoom: bool,
+    x_zoom_modifiers: Modifiers,
+    y_zoom_modifiers: Modifiers,
+    scroll_pans: bool,
+    pan_inertia: bool,
+    data_version: Option<u64>,
+    follow_x: Option<f64>,
+    follow_latest: Option<f64>,
+    follow_fit_y: bool,
+    tick_marks: TickMarks,
+    grid_on_top: bool,
+    background_color: Option<Color32>,
+    frame_stroke: Option<Stroke>,
+    show_background: bool,
+    show_axes_lines: bool,
+    axes_lines_stroke: Option<Stroke>,
+    axes_lines_on_top: bool,
+    title_style: TitleStyle,
+    tick_text_style: TextStyle,
+    axis_label_text_style: TextStyle,
+    tick_labels_inside: bool,
+    y_tick_side: Side,
+    x_tick_label_overrides: Vec<(f64, String)>,
+    x_tick_override_color: Option<Color32>,
+    show_legend: bool,
+    hover_tooltip: bool,
+    hover_readout: bool,
+    crosshair: bool,
+    crosshair_stroke: Option<Stroke>,
+    crosshair_snap: bool,
+    snap_cursor: bool,
+    rect_selection: bool,
+    context_menu: bool,
+    show_toolbar: bool,
+    toolbar_auto_hide: bool,
+    context_menu_entries: Option<ContextMenuEntries<'mem>>,
+    retain: Option<RetainItems<'mem>>,
+    bounds_margin: Vec2,
+    bounds_margin_abs: Vec2,
+}
+
+pub(crate) struct PlotMemory {
+    last_drag_pos: Option<Pos2>,
+    x_axis_range: AxisRange,
+    y_axis_range: AxisRange,
+    secondary_y_range: Option<AxisRange>,
+    /// The ranges last requested through the builder. A new request overrides the current view.
+    requested_ranges: Option<(AxisRange, AxisRange, Option<AxisRange>)>,
+    /// The bounds last requested with `Plot::zoom_to`. Only a change is applied.
+    requested_zoom: Option<PlotBounds>,
+    /// Whether the view follows new data. Panning or zooming suspends this, double clicking
+    /// resumes it.
+    following: bool,
+    /// The data of the previous frame, for following it.
+    data: DataExtent,
+    /// Start and current position of a box zoom selection, in screen coordinates.
+    box_zoom: Option<(Pos2, Pos2)>,
+    /// Names of the items that were hidden by clicking their legend entry.
+    hidden_items: HashSet<String>,
+    /// The item whose legend entry is hovered.
+    highlighted_item: Option<String>,
+    /// Start of a measurement, in plot coordinates.
+    measure_anchor: Option<Value>,
+    selected_points: Vec<SelectedPoint>,
+    /// The draggable point under the pointer in the last frame.
+    hovered_handle: Option<(Id, usize)>,
+    /// The point being dragged.
+    grabbed_point: Option<(Id, usize)>,
+    /// Where the context menu was opened, in screen and in plot coordinates.
+    context_menu: Option<(Pos2, Value)>,
+    /// Hidden through the context menu.
+    grid_hidden: bool,
+    /// A change of the view to apply the next time the plot is shown.
+    view_request: Option<ViewRequest>,
+    /// The data point closest to the pointer in the last frame.
+    snapped_point: Option<Value>,
+    /// Start and current position of a rectangular selection in screen coordinates, and
+    /// whether it adds to the selection.
+    selection_rect: Option<(Pos2, Pos2, bool)>,
+    /// Views stored with `PlotCtx::save_view`, in the order they were saved.
+    saved_views: Vec<(String, SavedView)>,
+    /// The x and y scaling toggled by the user, which overrides the scaling set when building
+    /// the plot.
+    scaling: Option<(AxisScaling, AxisScaling)>,
+    /// Where the legend was moved to, see `place_legend`.
+    legend_position: (Align2, Vec2),
+    /// The legend in the last frame, in screen coordinates.
+    legend_rect: Option<Rect>,
+    /// Where the legend is held while dragging it, relative to its top left corner.
+    legend_grab: Option<Vec2>,
+    /// The center of the fingers during a multi-touch gesture that started on the plot.
+    touch_centroid: Option<Pos2>,
+    /// Frame durations and pans of the last frames of a drag, in scaled plot coordinates.
+    pan_history: VecDeque<(f32, Vec2)>,
+    /// The velocity of the pan inertia, in scaled plot coordinates per second.
+    pan_velocity: Option<Vec2>,
+    /// The shapes of the items in the last frame, see `Plot::data_version`.
+    shape_cache: Option<(ShapeCacheKey, Vec<Shape>)>,
+    /// The grid, tick marks and tick labels in the last frame, see `Plot::data_version`.
+    axis_cache: Option<(ShapeCacheKey, AxisShapes)>,
+    /// The items kept with `Plot::retain` and the data version they were updated for.
+    retained_items: Option<(Option<u64>, BoxedItems)>,
+    /// Buffers the items build their geometry in, see `PlotPainter::scratch`.
+    scratch_buffers: Vec<Vec<Pos2>>,
+    /// The emptied list of the item shapes of an earlier frame, reused for the next one.
+    shape_buffer: Vec<Shape>,
+}
+
+/// What the shapes of the items depend on besides the data.
+#[derive(Clone, PartialEq)]
+struct ShapeCacheKey {
+    data_version: u64,
+    ranges: (AxisRange, AxisRange, Option<AxisRange>),
+    painter_rect: Rect,
+    hidden_items: HashSet<String>,
+    highlighted_item: Option<String>,
+}
+
+/// The shapes around the items, which are laid out before the items are painted.
+#[derive(Clone)]
+struct AxisShapes {
+    grid: Vec<Shape>,
+    axes_lines: Vec<Shape>,
+    /// The tick marks and the labels outside the plot area.
+    labels: Vec<Shape>,
+    /// The labels inside the plot area, painted above the items.
+    inside_labels: Vec<(Pos2, String, Color32)>,
+}
+
+/// The visible ranges of a plot, stored under a name with `PlotCtx::save_view`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct SavedView {
+    pub x_range: RangeInclusive<f64>,
+    pub y_range: RangeInclusive<f64>,
+}
+
+/// A change of the view requested through `PlotCtx` or the context menu.
+enum ViewRequest {
+    Set(RangeInclusive<f64>, RangeInclusive<f64>),
+    ZoomTo(PlotBounds),
+    /// Zoom around the center by a fraction of the extent, in with negative values.
+    Zoom(f64),
+    Reset,
+    Fit,
+}
+
+impl Default for PlotMemory {
+    fn default() -> Self {
+        Self {
+            last_drag_pos: None,
+            x_axis_range: AxisRange::new((-10.)..=10.),
+            y_axis_range: AxisRange::new((-10.)..=10.),
+            secondary_y_range: None,
+            requested_ranges: None,
+            requested_zoom: None,
+            following: true,
+            data: DataExtent::default(),
+            box_zoom: None,
+            hidden_items: HashSet::new(),
+            highlighted_item: None,
+            measure_anchor: None,
+            selected_points: Vec::new(),
+            hovered_handle: None,
+            grabbed_point: None,
+            context_menu: None,
+            grid_hidden: false,
+            view_request: None,
+            snapped_point: None,
+            selection_rect: None,
+            saved_views: Vec::new(),
+            scaling: None,
+            legend_position: (Align2::RIGHT_TOP, vec2(-LEGEND_MARGIN, LEGEND_MARGIN)),
+            legend_rect: None,
+            legend_grab: None,
+            touch_centroid: None,
+            pan_history: VecDeque::with_capacity(PAN_HISTORY + 1),
+            pan_velocity: None,
+            shape_cache: None,
+            axis_cache: None,
+            retained_items: None,
+            scratch_buffers: Vec::new(),
+            shape_buffer: Vec::new(),
+        }
+    }
+}
+
+/// The shared ranges of linked axes, by group name.
+#[derive(Default)]
+pub(crate) struct LinkGroups {
+    x: HashMap<String, (f64, f64)>,
+    y: HashMap<String, (f64, f64)>,
+}
+
+#[derive(Default)]
+pub struct PlotCtx {
+    pub(crate) memory: HashMap<Id, PlotMemory>,
+    pub(crate) link_groups: LinkGroups,
+}
+
+impl PlotCtx {
+    pub fn plot(&mut self, label: impl Into<String>) -> Plot {
+        let id = Id::new(label.into());
+        let memory = self.memory.entry(id).or_default();
+        Plot::new_with_memory(memory, &mut self.link_groups)
+    }
+
+    /// Show the given ranges the next time the plot is shown. Ongoing dragging is cancelled.
+    pub fn set_view(
+        &mut self,
+        label: impl Into<String>,
+        x_range: RangeInclusive<f64>,
+        y_range: RangeInclusive<f64>,
+    ) {
+        self.request_view(label, ViewRequest::Set(x_range, y_range));
+    }
+
+    /// The visible ranges of a plot, or `None` if it has not been shown yet.
+    pub fn get_view(
+        &self,
+        label: impl Into<String>,
+    ) -> Option<(RangeInclusive<f64>, RangeInclusive<f64>)> {
+        let memory = self.memory.get(&Id::new(label.into()))?;
+        memory.requested_ranges?;
+        let (x, y) = (memory.x_axis_range, memory.y_axis_range);
+        Some((x.start..=x.end, y.start..=y.end))
+    }
+
+    /// Go back to the ranges set when building the plot, and resume following.
+    pub fn reset_view(&mut self, label: impl Into<String>) {
+        self.request_view(label, ViewRequest::Reset);
+    }
+
+    /// Fit the view to the data of the items on the primary axes.
+    pub fn zoom_to_fit(&mut self, label: impl Into<String>) {
+        self.request_view(label, ViewRequest::Fit);
+    }
+
+    /// Show exactly `bounds`, in plot coordinates, the next time the plot is shown. With an
+    /// aspect ratio the smaller dimension is expanded. The zoom limits and bounds still apply.
+    pub fn zoom_to(&mut self, label: impl Into<String>, bounds: impl Into<PlotBounds>) {
+        self.request_view(label, ViewRequest::ZoomTo(bounds.into()));
+    }
+
+    /// Store the visible ranges of a plot under `name`, replacing a view of the same name. Does
+    /// nothing if the plot has not been shown yet.
+    pub fn save_view(&mut self, label: impl Into<String>, name: impl Into<String>) {
+        let label = label.into();
+        if let Some((x_range, y_range)) = self.get_view(label.clone()) {
+            self.insert_view(label, name, SavedView { x_range, y_range });
+        }
+    }
+
+    /// Store a view under `name`, e.g. one that was persisted by the app.
+    pub fn insert_view(
+        &mut self,
+        label: impl Into<String>,
+        name: impl Into<String>,
+        view: SavedView,
+    ) {
+        let name = name.into();
+        let memory = self.memory.entry(Id::new(label.into())).or_default();
+        match memory
+            .saved_views
+            .iter_mut()
+            .find(|(saved, _)| *saved == name)
+        {
+            Some((_, saved)) => *saved = view,
+            None => memory.saved_views.push((name, view)),
+        }
+    }
+
+    /// Show a view stored under `name` the next time the plot is shown, like `set_view`.
+    /// Returns false if there is no such view.
+    pub fn restore_view(&mut self, label: impl Into<String>, name: &str) -> bool {
+        let label = label.into();
+        match self.saved_view(label.clone(), name) {
+            Some(view) => {
+                self.set_view(label, view.x_range, view.y_range);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The view stored under `name`.
+    pub fn saved_view(&self, label: impl Into<String>, name: &str) -> Option<SavedView> {
+        let memory = self.memory.get(&Id::new(label.into()))?;
+        memory
+            .saved_views
+            .iter()
+            .find(|(saved, _)| saved == name)
+            .map(|(_, view)| view.clone())
+    }
+
+    /// The names of the stored views of a plot, in the order they were saved.
+    pub fn list_views(&self, label: impl Into<String>) -> Vec<String> {
+        self.memory
+            .get(&Id::new(label.into()))
+            .map(|memory| {
+                memory
+                    .saved_views
+                    .iter()
+                    .map(|(name, _)| name.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn request_view(&mut self, label: impl Into<String>, request: ViewRequest) {
+        let memory = self.memory.entry(Id::new(label.into())).or_default();
+        memory.view_request = Some(request);
+    }
+}
+
+impl<'mem> Plot<'mem> {
+    fn new_with_memory(memory: &'mem mut PlotMemory, link_groups: &'mem mut LinkGroups) -> Self {
+        Self {
+            title: None,
+            show_cursor_pos: true,
+            cursor_formatter: None,
+            copy_formatter: None,
+            cursor_readout_corner: Align2::RIGHT_BOTTOM,
+            memory,
+            link_groups,
+            link_x: None,
+            link_y: None,
+            size: vec2(100., 100.),
+            x_axis: Axis::default(),
+            y_axis: Axis::default(),
+            aspect_ratio: None,
+            log_minor_ticks: true,
+            x_time: None,
+            secondary_y_axis: None,
+            top_x_axis: None,
+            margins: Margins::default(),
+            min_zoom_extent: Vec2::splat(1e-6),
+            max_zoom_extent: Vec2::splat(1e12),
+            bounds: None,
+            zoom_to: None,
+            lock_x: false,
+            lock_y: false,
+            allow_keyboard: false,
+            allow_scale_toggle: false,
+            allow_drag: true,
+            interaction: InteractionSettings::default(),
+            allow_zoom: true,
+            zoom_speed: 1.01,
+            invert_zoom: false,
+            x_zoom_modifiers: Modifiers {
+                ctrl: true,
+                ..Default::default()
+            },
+            y_zoom_modifiers: Modifiers {
+                shift: true,
+                ..Default::default()
+            },
+            scroll_pans: false,
+            pan_inertia: false,
+            data_version: None,
+            follow_x: None,
+            follow_latest: None,
+            follow_fit_y: false,
+            tick_marks: TickMarks::default(),
+            grid_on_top: false,
+            background_color: None,
+            frame_stroke: None,
+            show_background: true,
+            show_axes_lines: false,
+            axes_lines_stroke: None,
+            axes_lines_on_top: false,
+            title_style: TitleStyle::default(),
+            tick_text_style: TextStyle::Monospace,
+            axis_label_text_style: TextStyle::Monospace,
+            tick_labels_inside: false,
+            y_tick_side: Side::Left,
+            x_tick_label_overrides: Vec::new(),
+            x_tick_override_color: None,
+            show_legend: true,
+            hover_tooltip: false,
+            hover_readout: false,
+            crosshair: false,
+            crosshair_stroke: None,
+            crosshair_snap: false,
+            snap_cursor: false,
+            rect_selection: false,
+            context_menu: false,
+            show_toolbar: false,
+            toolbar_auto_hide: true,
+            context_menu_entries: None,
+            retain: None,
+            bounds_margin: Vec2::splat(0.05),
+            bounds_margin_abs: Vec2::ZERO,
+        }
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn size(mut self, size: Vec2) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn x_axis_label(mut self, label: impl Into<String>) -> Self {
+        self.x_axis.label = label.into();
+        self
+    }
+
+    pub fn y_axis_label(mut self, label: impl Into<String>) -> Self {
+        self.y_axis.label = label.into();
+        self
+    }
+
+    /// The unit of the x axis, e.g. "ms".
+    pub fn x_unit(mut self, unit: impl Into<String>) -> Self {
+        self.x_axis.unit = unit.into();
+        self
+    }
+
+    /// The unit of the y axis, e.g. "V".
+    pub fn y_unit(mut self, unit: impl Into<String>) -> Self {
+        self.y_axis.unit = unit.into();
+        self
+    }
+
+    /// Label ticks of axes that have a unit with SI prefixes, e.g. "12 µs".
+    pub fn si_prefixes(mut self, on: bool) -> Self {
+        self.x_axis.si_prefix = on;
+        self.y_axis.si_prefix = on;
+        self
+    }
+
+    pub fn x_axis_range(mut self, range: RangeInclusive<f64>) -> Self {
+        let scaling = self.x_axis.range.scaling;
+        self.x_axis.range = AxisRange::new(range);
+        self.x_axis.range.scaling = scaling;
+        self
+    }
+
+    pub fn y_axis_range(mut self, range: RangeInclusive<f64>) -> Self {
+        let scaling = self.y_axis.range.scaling;
+        self.y_axis.range = AxisRange::new(range);
+        self.y_axis.range.scaling = scaling;
+        self
+    }
+
+    pub fn x_ticks(mut self, spacing: TickSpacing) -> Self {
+        self.x_axis.ticks = spacing;
+        self
+    }
+
+    pub fn y_ticks(mut self, spacing: TickSpacing) -> Self {
+        self.y_axis.ticks = spacing;
+        self
+    }
+
+    /// Place the x ticks exactly at the given positions instead of choosing them automatically.
+    /// If `labels` is given, it needs to be parallel to `values`, which debug builds assert.
+    /// Values without a label get just enough decimals to tell them from their neighbors.
+    pub fn x_tick_values(mut self, values: Vec<f64>, labels: Option<Vec<String>>) -> Self {
+        debug_assert_labels_match(&values, &labels);
+        self.x_axis.tick_values = Some((values, labels));
+        self
+    }
+
+    /// Place the y ticks exactly at the given positions instead of choosing them automatically.
+    /// If `labels` is given, it needs to be parallel to `values`, which debug builds assert.
+    /// Values without a label get just enough decimals to tell them from their neighbors.
+    pub fn y_tick_values(mut self, values: Vec<f64>, labels: Option<Vec<String>>) -> Self {
+        debug_assert_labels_match(&values, &labels);
+        self.y_axis.tick_values = Some((values, labels));
+        self
+    }
+
+    /// Use a logarithmic x axis. The axis range must be positive.
+    pub fn x_log(mut self, on: bool) -> Self {
+        self.x_axis.range.scaling = if on {
+            AxisScaling::Logarithmic
+        } else {
+            AxisScaling::Linear
+        };
+        self
+    }
+
+    /// Use a logarithmic y axis. The axis range must be positive.
+    pub fn y_log(mut self, on: bool) -> Self {
+        self.y_axis.range.scaling = if on {
+            AxisScaling::Logarithmic
+        } else {
+            AxisScaling::Linear
+        };
+        self
+    }
+
+    /// Interpret x values as time in seconds and label the ticks with clock times and dates.
+    pub fn x_axis_time(mut self, format: TimeFormat) -> Self {
+        self.x_time = Some(format);
+        self
+    }
+
+    /// Add a second y axis along the right edge. Items added with `PlotUi::add_secondary` use it.
+    pub fn secondary_y_axis(mut self, axis: Axis) -> Self {
+        self.secondary_y_axis = Some(axis);
+        self
+    }
+
+    /// Add an x axis along the top edge, either mirroring the bottom axis or with its own scale.
+    pub fn top_x_axis(mut self, axis: TopAxis) -> Self {
+        self.top_x_axis = Some(axis);
+        self
+    }
+
+    /// Minimum margins around the plotting area.
+    pub fn margins(mut self, margins: Margins) -> Self {
+        self.margins = margins;
+        self
+    }
+
+    /// The smallest extent of the axes that can be reached by zooming in.
+    pub fn min_zoom_extent(mut self, extent: Vec2) -> Self {
+        self.min_zoom_extent = extent;
+        self
+    }
+
+    /// The largest extent of the axes that can be reached by zooming out.
+    pub fn max_zoom_extent(mut self, extent: Vec2) -> Self {
+        self.max_zoom_extent = extent;
+        self
+    }
+
+    /// Keep the visible ranges inside `bounds`, in plot coordinates, when panning and zooming.
+    pub fn bounds(mut self, bounds: impl Into<PlotBounds>) -> Self {
+        self.bounds = Some(bounds.into());
+        self
+    }
+
+    /// Show exactly `bounds`, in plot coordinates, like `PlotCtx::zoom_to`. Like the axis
+    /// ranges, the bounds are only applied when they change, so panning and zooming can move
+    /// away from them in between.
+    pub fn zoom_to(mut self, bounds: impl Into<PlotBounds>) -> Self {
+        self.zoom_to = Some(bounds.into());
+        self
+    }
+
+    /// Share the x axis range with all plots of the same `PlotCtx` linked to `group`. Panning
+    /// or zooming any of them moves all of them, and a plot joining the group adopts its range.
+    pub fn link_x_group(mut self, group: impl Into<String>) -> Self {
+        self.link_x = Some(group.into());
+        self
+    }
+
+    /// Share the y axis range with all plots linked to `group`, see `link_x_group`.
+    pub fn link_y_group(mut self, group: impl Into<String>) -> Self {
+        self.link_y = Some(group.into());
+        self
+    }
+
+    /// Share both axis ranges with all plots linked to `group`, see `link_x_group`.
+    pub fn link_group(self, group: impl Into<String>) -> Self {
+        let group = group.into();
+        self.link_x_group(group.clone()).link_y_group(group)
+    }
+
+    /// Keep the x axis fixed while panning and zooming. The range can still be set with
+    /// `x_axis_range`.
+    pub fn lock_x(mut self, on: bool) -> Self {
+        self.lock_x = on;
+        self
+    }
+
+    /// Keep the y axis fixed while panning and zooming. The range can still be set with
+    /// `y_axis_range`.
+    pub fn lock_y(mut self, on: bool) -> Self {
+        self.lock_y = on;
+        self
+    }
+
+    /// Pan with the arrow keys, zoom with +/- or page up/down and reset the view with home
+    /// while the plot is hovered. Holding shift makes the steps finer. C copies the pointer
+    /// position and shift+C the visible ranges to the clipboard. Off by default.
+    pub fn allow_keyboard(mut self, on: bool) -> Self {
+        self.allow_keyboard = on;
+        self
+    }
+
+    /// Switch the y axis between linear and logarithmic scaling with L while the plot is hovered,
+    /// and the x axis with shift+L. The choice is remembered and overrides `x_log` and `y_log`.
+    /// Off by default.
+    pub fn allow_scale_toggle(mut self, on: bool) -> Self {
+        self.allow_scale_toggle = on;
+        self
+    }
+
+    /// React to clicks and drags, and pan by scrolling. If disabled, they pass through to the
+    /// widgets below, so box zoom, the legend and picking points don't work either. On by
+    /// default.
+    pub fn allow_drag(mut self, on: bool) -> Self {
+        self.allow_drag = on;
+        self
+    }
+
+    /// Assign the drag gestures to pointer buttons and modifiers, e.g. to box zoom with the
+    /// primary and pan with the middle button. A binding must not be used by two gestures.
+    pub fn interaction(mut self, settings: InteractionSettings) -> Self {
+        debug_assert!(
+            settings.conflict().is_none(),
+            "{:?} is assigned to more than one gesture",
+            settings.conflict()
+        );
+        self.interaction = settings;
+        self
+    }
+
+    /// Zoom by scrolling and pinching, and with the keyboard. If disabled, scrolling is left to
+    /// the surrounding `ScrollArea`. On by default.
+    pub fn allow_zoom(mut self, on: bool) -> Self {
+        self.allow_zoom = on;
+        self
+    }
+
+    /// Factor by which the visible extent shrinks per point scrolled up. 1.01 by default.
+    pub fn zoom_speed(mut self, speed: f32) -> Self {
+        self.zoom_speed = speed;
+        self
+    }
+
+    /// Zoom out instead of in when scrolling up. Off by default.
+    pub fn invert_zoom(mut self, on: bool) -> Self {
+        self.invert_zoom = on;
+        self
+    }
+
+    /// Enable or disable dragging and zooming together, see `allow_drag` and `allow_zoom`.
+    /// Disabling also turns off keyboard navigation.
+    pub fn interactive(mut self, on: bool) -> Self {
+        self.allow_drag = on;
+        self.allow_zoom = on;
+        if !on {
+            self.allow_keyboard = false;
+        }
+        self
+    }
+
+    /// Modifiers that make scrolling zoom only the x or only the y axis. Ctrl and shift by
+    /// default, pass `Modifiers::default()` to disable either. Ignored with a fixed aspect ratio.
+    pub fn single_axis_zoom_modifiers(mut self, x: Modifiers, y: Modifiers) -> Self {
+        self.x_zoom_modifiers = x;
+        self.y_zoom_modifiers = y;
+        self
+    }
+
+    /// Pan with vertical scrolling like on a trackpad, instead of zooming like with a mouse
+    /// wheel. Holding shift pans horizontally instead. Pinching and ctrl+scroll zoom and
+    /// horizontal scrolling pans the x axis either way. Off by default.
+    pub fn scroll_pans(mut self, on: bool) -> Self {
+        self.scroll_pans = on;
+        self
+    }
+
+    /// Keep panning after a fast drag is released, slowing down until the plot comes to rest
+    /// or is interacted with again. Off by default.
+    pub fn pan_inertia(mut self, on: bool) -> Self {
+        self.pan_inertia = on;
+        self
+    }
+
+    /// The version of the data shown in the plot. While it stays the same, and the view and
+    /// the size of the plot don't change, the shapes of the items, the grid and the tick
+    /// labels are reused from the last frame instead of painting them again. Bump it whenever
+    /// the data or the settings of the plot change.
+    pub fn data_version(mut self, version: u64) -> Self {
+        self.data_version = Some(version);
+        self
+    }
+
+    /// Keep items in the plot and show them every frame, before the items added in `show`.
+    /// The function updates the kept items. It runs the first time and whenever
+    /// `data_version` changes, so static data doesn't have to be rebuilt every frame.
+    /// Kept items are not highlighted by hovering their legend entry.
+    pub fn retain(mut self, update: impl FnOnce(&mut Vec<Box<dyn PlotItem>>) + 'mem) -> Self {
+        self.retain = Some(Box::new(update));
+        self
+    }
+
+    /// How the tick labels of the x axis are formatted.
+    pub fn x_tick_format(mut self, format: TickFormat) -> Self {
+        self.x_axis.format = format;
+        self
+    }
+
+    /// How the tick labels of the y axis are formatted.
+    pub fn y_tick_format(mut self, format: TickFormat) -> Self {
+        self.y_axis.format = format;
+        self
+    }
+
+    /// How the decades of logarithmic axes are labeled.
+    pub fn log_labels(mut self, labels: LogLabels) -> Self {
+        self.x_axis.log_labels = labels;
+        self.y_axis.log_labels = labels;
+        self
+    }
+
+    /// Keep the newest data in view: the x range becomes the last `window` units before the
+    /// latest x value. Panning or zooming suspends this until the plot is double clicked.
+    pub fn follow_x(mut self, window: f64) -> Self {
+        self.follow_x = Some(window);
+        self
+    }
+
+    /// The latest x value to follow. By default the largest x value of the data is used.
+    pub fn follow_latest(mut self, x: f64) -> Self {
+        self.follow_latest = Some(x);
+        self
+    }
+
+    /// Fit the y range to the data within the followed window.
+    pub fn follow_fit_y(mut self, on: bool) -> Self {
+        self.follow_fit_y = on;
+        self
+    }
+
+    /// Resume following after it was suspended by panning or zooming.
+    pub fn resume_following(self) -> Self {
+        self.memory.following = true;
+        self
+    }
+
+    /// The length and direction of the tick marks.
+    pub fn tick_marks(mut self, tick_marks: TickMarks) -> Self {
+        self.tick_marks = tick_marks;
+        self
+    }
+
+    /// Paint the grid above the items instead of below, so filled items don't hide it.
+    pub fn grid_on_top(mut self, on: bool) -> Self {
+        self.grid_on_top = on;
+        self
+    }
+
+    /// The color of the plot area. Follows the visuals by default.
+    pub fn background_color(mut self, color: Color32) -> Self {
+        self.background_color = Some(color);
+        self
+    }
+
+    /// The frame around the plot area. Follows the visuals by default.
+    pub fn frame_stroke(mut self, stroke: Stroke) -> Self {
+        self.frame_stroke = Some(stroke);
+        self
+    }
+
+    /// Fill the plot area with the background color. Without it, the plot blends with the
+    /// surrounding panel.
+    pub fn show_background(mut self, on: bool) -> Self {
+        self.show_background = on;
+        self
+    }
+
+    /// Draw the lines x = 0 and y = 0 across the plot, when they are visible.
+    pub fn show_axes_lines(mut self, on: bool) -> Self {
+        self.show_axes_lines = on;
+        self
+    }
+
+    /// The stroke of the x = 0 and y = 0 lines. Derived from the text color by default.
+    pub fn axes_lines_stroke(mut self, stroke: Stroke) -> Self {
+        self.axes_lines_stroke = Some(stroke);
+        self
+    }
+
+    /// Draw the x = 0 and y = 0 lines above the items instead of below.
+    pub fn axes_lines_on_top(mut self, on: bool) -> Self {
+        self.axes_lines_on_top = on;
+        self
+    }
+
+    /// The text style of the title.
+    pub fn title_text_style(mut self, text_style: TextStyle) -> Self {
+        self.title_style.text_style = text_style;
+        self
+    }
+
+    /// The color, text style and alignment of the title.
+    pub fn title_style(mut self, style: TitleStyle) -> Self {
+        self.title_style = style;
+        self
+    }
+
+    /// The text style of the tick labels and the cursor position.
+    pub fn tick_text_style(mut self, text_style: TextStyle) -> Self {
+        self.tick_text_style = text_style;
+        self
+    }
+
+    /// The text style of the axis labels.
+    pub fn axis_label_text_style(mut self, text_style: TextStyle) -> Self {
+        self.axis_label_text_style = text_style;
+        self
+    }
+
+    /// Draw the tick labels of the bottom and left axes just inside the plot area, so the
+    /// margins can be small.
+    pub fn tick_labels_inside(mut self, on: bool) -> Self {
+        self.tick_labels_inside = on;
+        self
+    }
+
+    /// The side of the plot area with the y tick marks and labels. If there is a secondary y
+    /// axis, it occupies the right side.
+    pub fn y_tick_side(mut self, side: Side) -> Self {
+        self.y_tick_side = side;
+        self
+    }
+
+    /// Show the x axis in a second unit along the top edge, e.g. °F on top of °C. The top ticks
+    /// are placed at round values of the other unit.
+    pub fn x_axis_twin(
+        mut self,
+        to_other: impl Fn(f64) -> f64 + 'static,
+        from_other: impl Fn(f64) -> f64 + 'static,
+        formatter: impl Fn(f64) -> String + 'static,
+    ) -> Self {
+        self.top_x_axis = Some(TopAxis::Transformed {
+            to_top: Box::new(to_other),
+            from_top: Some(Box::new(from_other)),
+            formatter: Some(Box::new(formatter)),
+        });
+        self
+    }
+
+    /// Extra labeled ticks on the x axis, e.g. to mark an event. Automatic labels that would
+    /// overlap them are hidden.
+    pub fn x_tick_label_overrides(mut self, overrides: Vec<(f64, String)>) -> Self {
+        self.x_tick_label_overrides = overrides;
+        self
+    }
+
+    /// The color of the ticks added with `x_tick_label_overrides`.
+    pub fn x_tick_override_color(mut self, color: Color32) -> Self {
+        self.x_tick_override_color = Some(color);
+        self
+    }
+
+    /// Padding around ranges that are fitted to the data, as a fraction of their extent on each
+    /// side. Defaults to 0.05.
+    pub fn bounds_margin(mut self, fraction: f32) -> Self {
+        self.bounds_margin = Vec2::splat(fraction);
+        self
+    }
+
+    /// Like `bounds_margin`, but separately for the x and y axis.
+    pub fn bounds_margin_per_axis(mut self, fraction: Vec2) -> Self {
+        self.bounds_margin = fraction;
+        self
+    }
+
+    /// Padding around ranges that are fitted to the data, in plot units. Added to the relative
+    /// `bounds_margin`.
+    pub fn bounds_margin_abs(mut self, margin: Vec2) -> Self {
+        self.bounds_margin_abs = margin;
+        self
+    }
+
+    /// Show a legend with the named items. Clicking an entry hides or shows its item. On by
+    /// default.
+    pub fn show_legend(mut self, on: bool) -> Self {
+        self.show_legend = on;
+        self
+    }
+
+    /// Show the coordinates and name of the data point closest to the pointer.
+    pub fn hover_tooltip(mut self, on: bool) -> Self {
+        self.hover_tooltip = on;
+        self
+    }
+
+    /// Draw a vertical line at the pointer and list the value of every named item at that x,
+    /// interpolated between its points. Items without a value there show a dash.
+    pub fn hover_readout(mut self, on: bool) -> Self {
+        self.hover_readout = on;
+        self
+    }
+
+    /// Draw horizontal and vertical lines through the pointer while the plot is hovered, with
+    /// the values labeled at the axes. Off by default.
+    pub fn crosshair(mut self, on: bool) -> Self {
+        self.crosshair = on;
+        self
+    }
+
+    /// Stroke of the crosshair lines. Defaults to a faint version of the text color.
+    pub fn crosshair_stroke(mut self, stroke: Stroke) -> Self {
+        self.crosshair_stroke = Some(stroke);
+        self
+    }
+
+    /// Move the crosshair to the data point closest to the pointer, if there is one nearby.
+    pub fn crosshair_snap_to_data(mut self, on: bool) -> Self {
+        self.crosshair_snap = on;
+        self
+    }
+
+    /// Show the exact values of the data point closest to the pointer in the cursor readout,
+    /// and move the crosshair there, instead of using the raw pointer position.
+    pub fn snap_cursor(mut self, on: bool) -> Self {
+        self.snap_cursor = on;
+        self
+    }
+
+    /// Select the pickable points inside a rectangle dragged with ctrl held, see
+    /// `Scatter::interactable` and `InteractionSettings`. Holding shift as well adds to the
+    /// selection, escape cancels it. Off by default.
+    pub fn rect_selection(mut self, on: bool) -> Self {
+        self.rect_selection = on;
+        self
+    }
+
+    /// Open a menu with entries to reset or fit the view, toggle the grid and copy the clicked
+    /// position when the plot is right-clicked. Off by default.
+    pub fn context_menu(mut self, on: bool) -> Self {
+        self.context_menu = on;
+        self
+    }
+
+    /// Add entries to the context menu, which is enabled by this. The function receives the
+    /// clicked position in plot coordinates.
+    pub fn context_menu_entries(mut self, entries: impl FnOnce(&mut Ui, Value) + 'mem) -> Self {
+        self.context_menu = true;
+        self.context_menu_entries = Some(Box::new(entries));
+        self
+    }
+
+    /// Show buttons in the top left corner of the plot to reset the view, fit it to the data,
+    /// zoom in and out and toggle the grid. Off by default.
+    pub fn show_toolbar(mut self, on: bool) -> Self {
+        self.show_toolbar = on;
+        self
+    }
+
+    /// Only show the toolbar while the pointer is over the plot. On by default.
+    pub fn toolbar_auto_hide(mut self, on: bool) -> Self {
+        self.toolbar_auto_hide = on;
+        self
+    }
+
+    /// Show minor ticks at 2 to 9 times each decade on logarithmic axes. On by default.
+    pub fn log_minor_ticks(mut self, on: bool) -> Self {
+        self.log_minor_ticks = on;
+        self
+    }
+
+    /// Keep a fixed aspect ratio: one x unit is `ratio` times as wide on screen as one y unit.
+    /// `None` disables the adjustment, which is the default.
+    pub fn aspect_ratio(mut self, ratio: impl Into<Option<f32>>) -> Self {
+        self.aspect_ratio = ratio.into();
+        self
+    }
+
+    /// Adjust the axis ranges such that one unit on the x axis is as long as one unit on the
+    /// y axis. Off by default.
+    pub fn axis_equal(mut self, on: bool) -> Self {
+        self.aspect_ratio = if on { Some(1.) } else { None };
+        self
+    }
+
+    /// Show the cursor position in a corner of the plot, see `cursor_readout_corner`.
+    pub fn show_cursor_position(mut self, on: bool) -> Self {
+        self.show_cursor_pos = on;
+        self
+    }
+
+    /// Format the cursor position readout, e.g. to show units or derived quantities. The
+    /// function receives the cursor position in plot coordinates.
+    pub fn cursor_formatter(mut self, formatter: impl Fn(Value) -> String + 'static) -> Self {
+        self.cursor_formatter = Some(Box::new(formatter));
+        self
+    }
+
+    /// Format the text copied to the clipboard, e.g. as a CSV row or JSON. By default the
+    /// position is copied as "x, y" and the view as "x: a..b, y: c..d".
+    pub fn copy_formatter(mut self, formatter: impl Fn(&CopiedValue) -> String + 'static) -> Self {
+        self.copy_formatter = Some(Box::new(formatter));
+        self
+    }
+
+    /// The corner of the plot where the cursor position is shown. Bottom right by default.
+    pub fn cursor_readout_corner(mut self, corner: Align2) -> Self {
+        self.cursor_readout_corner = corner;
+        self
+    }
+
+    /// Draw the plot. Takes a closure where contents can be added to the plot.
+    ///
+    /// The plot only requests another frame while it is animating, i.e. during pan inertia or
+    /// while moving to a requested view. Static plots are repainted on input as usual.
+    pub fn show<R>(
+        self,
+        ui: &mut Ui,
+        add_contents: impl FnOnce(&mut PlotUi) -> R,
+    ) -> PlotResponse<R> {
+        let Self {
+            show_cursor_pos,
+            cursor_formatter,
+            copy_formatter,
+            cursor_readout_corner,
+            memory,
+            link_groups,
+            link_x,
+            link_y,
+            title,
+            size,
+            x_axis,
+            y_axis,
+            aspect_ratio,
+            log_minor_ticks,
+            x_time,
+            secondary_y_axis,
+            top_x_axis,
+            margins,
+            min_zoom_extent,
+            max_zoom_extent,
+            bounds,
+            zoom_to,
+            lock_x,
+            lock_y,
+            allow_keyboard,
+            allow_scale_toggle,
+            allow_drag,
+            interaction,
+            allow_zoom,
+            zoom_speed,
+            invert_zoom,
+            x_zoom_modifiers,
+            y_zoom_modifiers,
+            scroll_pans,
+            pan_inertia,
+            data_version,
+            follow_x,
+            follow_latest,
+            follow_fit_y,
+            tick_marks,
+            grid_on_top,
+            background_color,
+            frame_stroke,
+            show_background,
+            show_axes_lines,
+            axes_lines_stroke,
+            axes_lines_on_top,
+            title_style,
+            tick_text_style,
+            axis_label_text_style,
+            tick_labels_inside,
+            y_tick_side,
+            x_tick_label_overrides,
+            x_tick_override_color,
+            show_legend,
+            hover_tooltip,
+            hover_readout,
+            crosshair,
+            crosshair_stroke,
+            crosshair_snap,
+            snap_cursor,
+            rect_selection,
+            context_menu: show_context_menu,
+            show_toolbar,
+            toolbar_auto_hide,
+            context_menu_entries,
+            retain,
+            bounds_margin,
+            bounds_margin_abs,
+        } = self;
+        let controls = Controls {
+            lock_x,
+            lock_y,
+            allow_keyboard,
+            allow_scale_toggle,
+            allow_drag,
+            interaction,
+            allow_zoom,
+            zoom_speed,
+            invert_zoom,
+            x_zoom_modifiers,
+            y_zoom_modifiers,
+            scroll_pans,
+            pan_inertia,
+            rect_selection,
+            aspect_ratio: aspect_ratio.map(f64::from),
+            x_limits: min_zoom_extent.x as f64..=max_zoom_extent.x as f64,
+            y_limits: min_zoom_extent.y as f64..=max_zoom_extent.y as f64,
+            bounds,
+        };
+        let axis_style = AxisStyle {
+            top_x_axis,
+            y_tick_side,
+            margins,
+            log_minor_ticks,
+            x_time,
+            tick_marks,
+            tick_text_style,
+            axis_label_text_style,
+            tick_labels_inside,
+            x_tick_label_overrides,
+            x_tick_override_color,
+            show_axes_lines,
+            axes_lines_stroke,
+        };
+        let mut axes = PlotAxes {
+            x: x_axis,
+            y: y_axis,
+            secondary_y: secondary_y_axis,
+        };
+        let (x_margin, y_margin) = (bounds_margin.x as f64, bounds_margin.y as f64);
+        let (x_margin_abs, y_margin_abs) = (bounds_margin_abs.x as f64, bounds_margin_abs.y as f64);
+
+        Resize::default().default_size(size).show(ui, |ui| {
+            let range_requested = axes.restore_ranges(memory);
+
+            // Linked axes adopt the range of their group, unless a new range was requested after
+            // the first frame. Each plot starts from the range left by the previous one, so plots
+            // interacted with in the same frame add up instead of overriding each other.
+            if !range_requested {
+                link_groups.adopt(link_x.as_deref(), link_y.as_deref(), &mut axes);
+            }
+
+            if memory.requested_zoom != zoom_to {
+                if let Some(bounds) = zoom_to {
+                    memory.view_request = Some(ViewRequest::ZoomTo(bounds));
+                }
+                memory.requested_zoom = zoom_to;
+            }
+
+            // Fitting needs the data, so it is done after the items were added.
+            let request = memory.view_request.take();
+            let fit_requested = matches!(request, Some(ViewRequest::Fit));
+            let zoom_to_requested = matches!(request, Some(ViewRequest::ZoomTo(_)));
+            if let Some(request) = request {
+                controls.apply_request(request, memory, &mut axes);
+            }
+            if let Some((x_scaling, y_scaling)) = memory.scaling {
+                axes.x.range.scaling = x_scaling;
+                axes.y.range.scaling = y_scaling;
+            }
+
+            // The units become part of the axis labels.
+            axes.x.label = axes.x.full_label();
+            axes.y.label = axes.y.full_label();
+            if let Some(axis) = &mut axes.secondary_y {
+                axis.label = axis.full_label();
+            }
+
+            // Colors that are not set explicitly follow the visuals, for light and dark themes.
+            let visuals = &ui.style().visuals;
+            let text_color = visuals.text_color();
+            let background_color = background_color.unwrap_or(visuals.extreme_bg_color);
+            let frame_stroke = frame_stroke.unwrap_or(visuals.widgets.noninteractive.bg_stroke);
+
+            let (response, mut painter) = ui.allocate_painter(
+                ui.available_size_before_wrap(),
+                if allow_drag {
+                    Sense::click_and_drag()
+                } else {
+                    Sense::hover()
+                },
+            );
+
+            // Toggle the scaling before the ticks are computed, so they match at once.
+            controls.toggle_scaling(ui, &response, memory, &mut axes);
+
+            let title_galley = title.map(|title| {
+                let color = title_style.color.unwrap_or(text_color);
+                painter.layout_no_wrap(title, title_style.text_style, color)
+            });
+            let area = axis_style.layout(
+                &painter,
+                response.rect,
+                &axes,
+                title_galley.as_ref().map(|galley| galley.size().y),
+            );
+            let painter_rect = area.rect;
+            if show_background {
+                painter.rect(painter_rect, 0., background_color, frame_stroke);
+            } else {
+                painter.rect_stroke(painter_rect, 0., frame_stroke);
+            }
+
+            if let Some(galley) = title_galley {
+                let x = match title_style.align {
+                    Align::Min => painter_rect.left(),
+                    Align::Center => painter_rect.center().x - galley.size().x / 2.,
+                    Align::Max => painter_rect.right() - galley.size().x,
+                };
+                let y = painter_rect.top()
+                    - 2.
+                    - axis_style.top_labels_height(&painter)
+                    - galley.size().y;
+                painter.galley(pos2(x, y), galley);
+            }
+            axis_style.paint_labels(&painter, &area, &axes, text_color);
+
+            controls.apply_aspect_ratio(painter_rect, &mut axes, zoom_to_requested);
+            let released_selection = controls.interact(ui, &response, &area, memory, &mut axes);
+
+            // Following
+            if response.double_clicked() {
+                memory.following = true;
+            }
+            if let Some(window) = follow_x.filter(|_| memory.following) {
+                if let Some(latest) = follow_latest.or(memory.data.latest_x) {
+                    let range = padded_range(latest - window, latest, x_margin, x_margin_abs);
+                    axes.x.range.start = *range.start();
+                    axes.x.range.end = *range.end();
+                }
+                if let Some((min, max)) = memory.data.window_y.filter(|_| follow_fit_y) {
+                    let range = padded_range(min, max, y_margin, y_margin_abs);
+                    axes.y.range.start = *range.start();
+                    axes.y.range.end = *range.end();
+                }
+            }
+
+            if let Some(bounds) = controls.bounds {
+                axes.x.range.clamp_to(bounds.x_range());
+                axes.y.range.clamp_to(bounds.y_range());
+            }
+
+            axes.x.range.sanitize();
+            axes.y.range.sanitize();
+            if let Some(axis) = &mut axes.secondary_y {
+                axis.range.sanitize();
+            }
+
+            let transform = ScreenTransform::from_axes(painter_rect, axes.x.range, axes.y.range);
+            let secondary_transform = axes
+                .secondary_y
+                .as_ref()
+                .map(|axis| ScreenTransform::from_axes(painter_rect, axes.x.range, axis.range));
+
+            // The shapes of the items are replayed from the last frame if neither the data nor
+            // the view changed.
+            let cache_key = data_version.map(|data_version| ShapeCacheKey {
+                data_version,
+                ranges: (
+                    axes.x.range,
+                    axes.y.range,
+                    axes.secondary_y.as_ref().map(|axis| axis.range),
+                ),
+                painter_rect,
+                hidden_items: memory.hidden_items.clone(),
+                highlighted_item: memory.highlighted_item.clone(),
+            });
+            // The grid, tick marks and labels only depend on the same things, so they are reused
+            // as well instead of laying out the labels again.
+            let axis_shapes = match &memory.axis_cache {
+                Some((key, shapes)) if Some(key) == cache_key.as_ref() => shapes.clone(),
+                _ => axis_style.shapes(&TickLayout {
+                    painter: &painter,
+                    rect: painter_rect,
+                    transform,
+                    axes: &axes,
+                    text_color,
+                }),
+            };
+            memory.axis_cache = cache_key.clone().map(|key| (key, axis_shapes.clone()));
+            let AxisShapes {
+                mut grid,
+                mut axes_lines,
+                labels,
+                inside_labels,
+            } = axis_shapes;
+            painter.extend(labels);
+
+            // Restrict painting to the painter area
+            painter.set_clip_rect(painter_rect);
+
+            if memory.grid_hidden {
+                grid.clear();
+            }
+            if !grid_on_top {
+                painter.extend(std::mem::take(&mut grid));
+            }
+            if !axes_lines_on_top {
+                painter.extend(std::mem::take(&mut axes_lines));
+            }
+
+            let replaying = cache_key.is_some()
+                && memory.shape_cache.as_ref().map(|(key, _)| key) == cache_key.as_ref();
+
+            // Call the function provided by the user to add the shapes.
+            let mut plot_ui = PlotUi {
+                painter: PlotPainter::new(
+                    &painter,
+                    std::mem::take(&mut memory.shape_buffer),
+                    std::mem::take(&mut memory.scratch_buffers),
+                ),
+                replaying,
+                transform,
+                secondary_transform,
+                mouse_position: ui
+                    .input()
+                    .pointer
+                    .interact_pos()
+                    .map(|pos| transform.value(&pos)),
+                hovered: ui
+                    .input()
+                    .pointer
+                    .interact_pos()
+                    .filter(|pos| painter_rect.contains(*pos))
+                    .is_some(),
+                data: follow_x.map(|_| DataExtent::default()),
+                x_window: axes.x.range.start..=axes.x.range.end,
+                legend_entries: Vec::new(),
+                hidden_items: &memory.hidden_items,
+                highlighted_item: memory.highlighted_item.as_deref(),
+                hover: ui
+                    .input()
+                    .pointer
+                    .hover_pos()
+                    .filter(|pos| {
+                        (hover_tooltip || snap_cursor || crosshair && crosshair_snap)
+                            && painter_rect.contains(*pos)
+                    })
+                    .map(|pointer| {
+                        let radius = 10.;
+                        let left = transform.value(&(pointer - vec2(radius, 0.))).x;
+                        let right = transform.value(&(pointer + vec2(radius, 0.))).x;
+                        HoverSearch {
+                            pointer,
+                            x_window: left..=right,
+                            radius,
+                            nearest: None,
+                            previous: memory.snapped_point,
+                        }
+                    }),
+                readout: ui
+                    .input()
+                    .pointer
+                    .hover_pos()
+                    .filter(|pos| hover_readout && painter_rect.contains(*pos))
+                    .map(|pointer| HoverReadout {
+                        x: transform.value(&pointer).x,
+                        rows: Vec::new(),
+                    }),
+                click_pos: ui
+                    .input()
+                    .pointer
+                    .interact_pos()
+                    .filter(|_| response.clicked()),
+                picked_point: None,
+                selected_points: &memory.selected_points,
+                selection_marks: Vec::new(),
+                hover_pos: ui
+                    .input()
+                    .pointer
+                    .hover_pos()
+                    .filter(|pos| painter_rect.contains(*pos)),
+                hovered_item: None,
+                hovered_handle: None,
+                grabbed_point: memory
+                    .grabbed_point
+                    .zip(response.interact_pointer_pos())
+                    .map(|(point, pos)| (point, painter_rect.clamp(pos))),
+                dragged_point: None,
+                dragged_line: None,
+                cursor: None,
+                fit_bounds: if fit_requested {
+                    Some(PlotBounds::NOTHING)
+                } else {
+                    None
+                },
+                select_rect: released_selection.map(|(rect, _)| rect),
+                rect_selected: Vec::new(),
+                lock_x,
+                lock_y,
+            };
+            match retain {
+                Some(update) => {
+                    let stale = match &memory.retained_items {
+                        Some((version, _)) => *version != data_version,
+                        None => true,
+                    };
+                    if stale {
+                        let mut items = memory
+                            .retained_items
+                            .take()
+                            .map(|(_, items)| items)
+                            .unwrap_or_default();
+                        update(&mut items);
+                        memory.retained_items = Some((data_version, items));
+                    }
+                }
+                None => memory.retained_items = None,
+            }
+            if let Some((_, items)) = &mut memory.retained_items {
+                items
+                    .iter_mut()
+                    .for_each(|item| plot_ui.add_item(item.as_mut(), false));
+            }
+            let inner = add_contents(&mut plot_ui);
+            let (mut item_shapes, buffers) = plot_ui.painter.into_parts();
+            memory.scratch_buffers = buffers;
+            match cache_key {
+                Some(key) => {
+                    if replaying {
+                        item_shapes.clear();
+                        memory.shape_buffer = item_shapes;
+                    } else if let Some((_, mut replaced)) =
+                        memory.shape_cache.replace((key, item_shapes))
+                    {
+                        replaced.clear();
+                        memory.shape_buffer = replaced;
+                    }
+                    if let Some((_, shapes)) = &memory.shape_cache {
+                        painter.extend(shapes.clone());
+                    }
+                }
+                None => {
+                    memory.shape_cache = None;
+                    // Not `Painter::extend`, which would drop the list.
+                    item_shapes.drain(..).for_each(|shape| {
+                        painter.add(shape);
+                    });
+                    memory.shape_buffer = item_shapes;
+                }
+            }
+            let plot_hovered = plot_ui.hovered;
+            if let Some(plot_data) = plot_ui.data {
+                memory.data = plot_data;
+            }
+            let legend_entries = plot_ui.legend_entries;
+            let nearest_point = plot_ui.hover.and_then(|hover| hover.nearest);
+            let readout = plot_ui.readout;
+            memory.snapped_point = nearest_point.as_ref().map(|nearest| nearest.value);
+            let picked_point = plot_ui.picked_point.map(|(_, point)| point);
+            let dragged_point = plot_ui.dragged_point;
+            let dragged_line = plot_ui.dragged_line;
+            let hovered_item = plot_ui.hovered_item;
+            let fit_bounds = plot_ui.fit_bounds;
+            let rect_selected = plot_ui.rect_selected;
+            let hovered = plot_ui.hovered_handle;
+            memory.hovered_handle = hovered.map(|(_, handle, _)| handle);
+            let cursor = plot_ui
+                .cursor
+                .or_else(|| hovered.map(|(_, _, cursor)| cursor));
+            if let Some(cursor) = cursor {
+                ui.output().cursor_icon = cursor;
+            }
+            plot_ui.selection_marks.iter().for_each(|(pos, radius)| {
+                painter.circle_stroke(*pos, radius + 2., Stroke::new(2., text_color));
+            });
+            painter.extend(grid);
+            painter.extend(axes_lines);
+            if let Some((start, end, _)) = memory.selection_rect {
+                let selection = ui.visuals().selection.bg_fill;
+                painter.rect(
+                    Rect::from_two_pos(start, end),
+                    0.,
+                    selection.linear_multiply(0.2),
+                    Stroke::new(1., selection),
+                );
+            }
+            if let Some((start, end)) = memory.box_zoom {
+                let selection = Rect::from_two_pos(start, end);
+                painter.rect(
+                    selection,
+                    0.,
+                    text_color.linear_multiply(0.05),
+                    Stroke::new(1., text_color.linear_multiply(0.5)),
                 );
-                i_start += 1;
+                // The region that will actually be shown.
+                if controls.keeps_aspect() && selection.width() > 4. && selection.height() > 4. {
+                    painter.rect_stroke(
+                        expand_to_aspect(selection, painter_rect),
+                        0.,
+                        Stroke::new(1., text_color.linear_multiply(0.2)),
+                    );
+                }
+            }
+
+            let overlay = Overlay {
+                painter: &painter,
+                rect: painter_rect,
+                transform,
+                axes: &axes,
+                text_style: tick_text_style,
+                text_color,
+                background_color,
+                frame_stroke,
+            };
+            if let (Some(anchor), Some(pointer)) =
+                (memory.measure_anchor, response.interact_pointer_pos())
+            {
+                overlay.measurement(anchor, pointer);
+            }
+            overlay.inside_labels(inside_labels);
+
+            // Crosshair through the pointer, or through the closest data point when snapping.
+            if crosshair {
+                let snapped = nearest_point
+                    .as_ref()
+                    .filter(|_| crosshair_snap || snap_cursor)
+                    .map(|nearest| nearest.screen_pos);
+                let pointer = ui
+                    .input()
+                    .pointer
+                    .interact_pos()
+                    .filter(|pos| painter_rect.contains(*pos));
+                if let Some(pos) = pointer.map(|pos| snapped.unwrap_or(pos)) {
+                    let stroke = crosshair_stroke
+                        .unwrap_or_else(|| Stroke::new(1., text_color.linear_multiply(0.5)));
+                    overlay.crosshair(pos, stroke);
+                }
+            }
+
+            // Values of all named items at the pointer x, next to a vertical line.
+            if let Some(readout) = readout {
+                let top = ui
+                    .input()
+                    .pointer
+                    .hover_pos()
+                    .map_or(painter_rect.top(), |p| p.y);
+                overlay.readout(readout, top);
+            }
+
+            memory.highlighted_item = None;
+            memory.legend_rect = None;
+            if show_legend && !legend_entries.is_empty() {
+                overlay.legend(ui, &response, &legend_entries, memory);
+            }
+
+            // Clicking selects the closest pickable point, shift adds or removes it instead.
+            let selected_points = &mut memory.selected_points;
+            if response.clicked() && memory.highlighted_item.is_none() {
+                let shift = ui.input().modifiers.shift;
+                match picked_point {
+                    Some(point) if shift => {
+                        let same = |selected: &SelectedPoint| {
+                            selected.item == point.item && selected.index == point.index
+                        };
+                        match selected_points.iter().position(same) {
+                            Some(i) => {
+                                selected_points.remove(i);
+                            }
+                            None => selected_points.push(point),
+                        }
+                    }
+                    Some(point) => *selected_points = vec![point],
+                    None if shift => {}
+                    None => selected_points.clear(),
+                }
+            }
+
+            // A rectangular selection replaces the selection or adds to it.
+            if let Some((_, additive)) = released_selection {
+                if !additive {
+                    selected_points.clear();
+                }
+                rect_selected.into_iter().for_each(|point| {
+                    let same = |selected: &SelectedPoint| {
+                        selected.item == point.item && selected.index == point.index
+                    };
+                    if !selected_points.iter().any(same) {
+                        selected_points.push(point);
+                    }
+                });
+            }
+
+            // Show the data point closest to the pointer.
+            if let Some(nearest) = nearest_point.as_ref().filter(|_| snap_cursor) {
+                painter.circle_filled(nearest.screen_pos, 3., text_color);
+            }
+            if let Some(nearest) = nearest_point.as_ref().filter(|_| hover_tooltip) {
+                overlay.tooltip(nearest);
+            }
+
+            // Show mouse position
+            if show_cursor_pos {
+                if let Some(mouse_pos) = ui
+                    .input()
+                    .pointer
+                    .interact_pos()
+                    .filter(|pos| painter_rect.contains(*pos))
+                {
+                    let mouse_pos = match nearest_point.as_ref().filter(|_| snap_cursor) {
+                        Some(nearest) => nearest.value,
+                        None => transform.value(&mouse_pos),
+                    };
+                    let text = match &cursor_formatter {
+                        Some(formatter) => formatter(mouse_pos),
+                        None => format!(
+                            "{}, {}",
+                            axes.x.format_value(mouse_pos.x),
+                            axes.y.format_value(mouse_pos.y)
+                        ),
+                    };
+                    let inner = painter_rect.shrink(10.);
+                    let corner = pos2(
+                        inner.left() + inner.width() * cursor_readout_corner.x().to_factor(),
+                        inner.top() + inner.height() * cursor_readout_corner.y().to_factor(),
+                    );
+                    painter.text(
+                        corner,
+                        cursor_readout_corner,
+                        text,
+                        tick_text_style,
+                        text_color,
+                    );
+                }
+            }
+
+            // Copy the pointer position or the visible ranges to the clipboard.
+            let copy_text = |value: CopiedValue| match &copy_formatter {
+                Some(formatter) => formatter(&value),
+                None => match value {
+                    CopiedValue::Position(pos) => format!(
+                        "{}, {}",
+                        axes.x.format_value(pos.x),
+                        axes.y.format_value(pos.y)
+                    ),
+                    CopiedValue::View { x_range, y_range } => format!(
+                        "x: {}..{}, y: {}..{}",
+                        axes.x.format_value(*x_range.start()),
+                        axes.x.format_value(*x_range.end()),
+                        axes.y.format_value(*y_range.start()),
+                        axes.y.format_value(*y_range.end())
+                    ),
+                },
+            };
+            let view = || CopiedValue::View {
+                x_range: axes.x.range.start..=axes.x.range.end,
+                y_range: axes.y.range.start..=axes.y.range.end,
+            };
+            if allow_keyboard
+                && response.hovered()
+                && ui.memory().focus().is_none()
+                && ui.input().key_pressed(Key::C)
+            {
+                let copied = if ui.input().modifiers.shift {
+                    Some(view())
+                } else {
+                    ui.input()
+                        .pointer
+                        .hover_pos()
+                        .filter(|pos| painter_rect.contains(*pos))
+                        .map(|pos| match nearest_point.as_ref().filter(|_| snap_cursor) {
+                            Some(nearest) => nearest.value,
+                            None => transform.value(&pos),
+                        })
+                        .map(CopiedValue::Position)
+                };
+                if let Some(copied) = copied {
+                    ui.output().copied_text = copy_text(copied);
+                }
+            }
+
+            let mut action = None;
+            let pointer_over_plot = ui
+                .input()
+                .pointer
+                .hover_pos()
+                .filter(|pos| response.rect.contains(*pos))
+                .is_some();
+            if show_toolbar && (pointer_over_plot || !toolbar_auto_hide) {
+                let pos = painter_rect.left_top() + vec2(5., 5.);
+                action = toolbar(ui, response.id, pos, allow_zoom);
+            }
+
+            // Context menu, opened by a right click without dragging.
+            let opened_menu = ui
+                .input()
+                .pointer
+                .interact_pos()
+                .filter(|_| show_context_menu && response.secondary_clicked())
+                .map(|pos| (pos, transform.value(&pos)));
+            if let Some(position) = memory.context_menu {
+                action = context_menu(ui, response.id, position, context_menu_entries).or(action);
+                if ui.input().pointer.any_click() || ui.input().key_pressed(Key::Escape) {
+                    memory.context_menu = None;
+                }
+            }
+            let request = match action {
+                Some(PlotAction::ResetView) => Some(ViewRequest::Reset),
+                Some(PlotAction::AutoFit) => Some(ViewRequest::Fit),
+                Some(PlotAction::ZoomIn) => Some(ViewRequest::Zoom(-TOOLBAR_ZOOM_STEP)),
+                Some(PlotAction::ZoomOut) => Some(ViewRequest::Zoom(TOOLBAR_ZOOM_STEP)),
+                Some(PlotAction::ToggleGrid) => {
+                    memory.grid_hidden = !memory.grid_hidden;
+                    None
+                }
+                Some(PlotAction::CopyPosition(pos)) => {
+                    ui.output().copied_text = copy_text(CopiedValue::Position(pos));
+                    None
+                }
+                Some(PlotAction::CopyView) => {
+                    ui.output().copied_text = copy_text(view());
+                    None
+                }
+                None => None,
+            };
+            if request.is_some() {
+                memory.view_request = request;
+                ui.ctx().request_repaint();
+            }
+            if opened_menu.is_some() {
+                memory.context_menu = opened_menu;
+            }
+
+            // Fit the view to the data collected from the items, as requested.
+            if let Some(bounds) = fit_bounds.filter(|bounds| bounds.is_finite()) {
+                memory.following = false;
+                ui.ctx().request_repaint();
+                if !lock_x {
+                    let range = padded_range(bounds.min.x, bounds.max.x, x_margin, x_margin_abs);
+                    axes.x.range.start = *range.start();
+                    axes.x.range.end = *range.end();
+                }
+                if !lock_y {
+                    let range = padded_range(bounds.min.y, bounds.max.y, y_margin, y_margin_abs);
+                    axes.y.range.start = *range.start();
+                    axes.y.range.end = *range.end();
+                }
+            }
+
+            link_groups.share(link_x, link_y, &axes);
+            let secondary_range = axes.secondary_y.as_ref().map(|axis| axis.range);
+            let view_changed = memory.x_axis_range != axes.x.range
+                || memory.y_axis_range != axes.y.range
+                || memory.secondary_y_range != secondary_range;
+            memory.x_axis_range = axes.x.range;
+            memory.y_axis_range = axes.y.range;
+            memory.secondary_y_range = secondary_range;
+
+            // Clicks on the legend don't count.
+            let clicked_item =
+                hovered_item.filter(|_| response.clicked() && memory.highlighted_item.is_none());
+            PlotResponse {
+                inner,
+                response,
+                pointer_position: ui
+                    .input()
+                    .pointer
+                    .hover_pos()
+                    .filter(|pos| painter_rect.contains(*pos))
+                    .map(|pos| {
+                        ScreenTransform::from_axes(painter_rect, axes.x.range, axes.y.range)
+                            .value(&pos)
+                    }),
+                hovered: plot_hovered,
+                bounds: PlotBounds::from_x_y_ranges(
+                    axes.x.range.start..=axes.x.range.end,
+                    axes.y.range.start..=axes.y.range.end,
+                ),
+                view_changed,
+                selected_points: memory.selected_points.clone(),
+                dragged_point,
+                dragged_line,
+                hovered_item,
+                clicked_item,
+                x_log: axes.x.range.is_log(),
+                y_log: axes.y.range.is_log(),
+            }
+        })
+    }
+}
+
+/// The axes of a plot while it is shown.
+struct PlotAxes {
+    x: Axis,
+    y: Axis,
+    secondary_y: Option<Axis>,
+}
+
+impl PlotAxes {
+    /// Continue from the ranges of the last frame, unless other ranges were requested when
+    /// building the plot. Returns whether they were requested after the first frame.
+    fn restore_ranges(&mut self, memory: &mut PlotMemory) -> bool {
+        let requested = Some((
+            self.x.range,
+            self.y.range,
+            self.secondary_y.as_ref().map(|axis| axis.range),
+        ));
+        if memory.requested_ranges != requested {
+            let range_requested = memory.requested_ranges.is_some();
+            memory.requested_ranges = requested;
+            return range_requested;
+        }
+        self.x.range = memory.x_axis_range;
+        self.y.range = memory.y_axis_range;
+        if let (Some(axis), Some(range)) = (&mut self.secondary_y, memory.secondary_y_range) {
+            axis.range = range;
+        }
+        false
+    }
+
+    /// The ranges of the primary and the secondary y axis, which pan and zoom together.
+    fn y_ranges(&mut self) -> impl Iterator<Item = &mut AxisRange> {
+        std::iter::once(&mut self.y.range)
+            .chain(self.secondary_y.as_mut().map(|axis| &mut axis.range))
+    }
+}
+
+impl LinkGroups {
+    /// Set linked axes to the range of their group.
+    fn adopt(&self, link_x: Option<&str>, link_y: Option<&str>, axes: &mut PlotAxes) {
+        if let Some(&(start, end)) = link_x.and_then(|group| self.x.get(group)) {
+            axes.x.range.start = start;
+            axes.x.range.end = end;
+        }
+        if let Some(&(start, end)) = link_y.and_then(|group| self.y.get(group)) {
+            axes.y.range.start = start;
+            axes.y.range.end = end;
+        }
+    }
+
+    /// Leave the ranges of linked axes for the other plots of their group.
+    fn share(&mut self, link_x: Option<String>, link_y: Option<String>, axes: &PlotAxes) {
+        if let Some(group) = link_x {
+            self.x.insert(group, (axes.x.range.start, axes.x.range.end));
+        }
+        if let Some(group) = link_y {
+            self.y.insert(group, (axes.y.range.start, axes.y.range.end));
+        }
+    }
+}
+
+/// Where the parts of a plot are on screen.
+#[derive(Clone, Copy)]
+struct PlotArea {
+    /// The full plot rectangle, including title, axes, and their labels.
+    full: Rect,
+    /// The rectangle that contains the plot items.
+    rect: Rect,
+    /// The margins with the tick labels act as axes that can be zoomed on their own.
+    x_strip: Rect,
+    y_strip: Rect,
+}
+
+/// How a plot reacts to input, from the settings of the `Plot`.
+struct Controls {
+    lock_x: bool,
+    lock_y: bool,
+    allow_keyboard: bool,
+    allow_scale_toggle: bool,
+    allow_drag: bool,
+    interaction: InteractionSettings,
+    allow_zoom: bool,
+    zoom_speed: f32,
+    invert_zoom: bool,
+    x_zoom_modifiers: Modifiers,
+    y_zoom_modifiers: Modifiers,
+    scroll_pans: bool,
+    pan_inertia: bool,
+    rect_selection: bool,
+    aspect_ratio: Option<f64>,
+    /// The smallest and largest extent the x axis can be zoomed to.
+    x_limits: RangeInclusive<f64>,
+    y_limits: RangeInclusive<f64>,
+    bounds: Option<PlotBounds>,
+}
+
+impl Controls {
+    /// Whether the view keeps its aspect ratio. Locking an axis turns this off.
+    fn keeps_aspect(&self) -> bool {
+        self.aspect_ratio.is_some() && !self.lock_x && !self.lock_y
+    }
+
+    /// Change the view as requested through `PlotCtx`, the toolbar or the context menu.
+    /// Requests cancel ongoing interactions. Fitting needs the data, so it is left to the caller.
+    fn apply_request(&self, request: ViewRequest, memory: &mut PlotMemory, axes: &mut PlotAxes) {
+        memory.last_drag_pos = None;
+        memory.pan_velocity = None;
+        memory.box_zoom = None;
+        memory.grabbed_point = None;
+        memory.measure_anchor = None;
+        match request {
+            ViewRequest::Set(x_range, y_range) => {
+                memory.following = false;
+                axes.x.range.start = *x_range.start();
+                axes.x.range.end = *x_range.end();
+                axes.y.range.start = *y_range.start();
+                axes.y.range.end = *y_range.end();
+            }
+            ViewRequest::ZoomTo(bounds) => {
+                memory.following = false;
+                axes.x
+                    .range
+                    .set_limited(bounds.x_range(), self.x_limits.clone());
+                axes.y
+                    .range
+                    .set_limited(bounds.y_range(), self.y_limits.clone());
+            }
+            ViewRequest::Zoom(amount) => {
+                memory.following = false;
+                if !self.lock_x {
+                    axes.x
+                        .range
+                        .zoom_limited(amount, 0.5, self.x_limits.clone());
+                }
+                if !self.lock_y {
+                    axes.y_ranges()
+                        .for_each(|range| range.zoom_limited(amount, 0.5, self.y_limits.clone()));
+                }
             }
+            ViewRequest::Reset => {
+                if let Some((x_range, y_range, secondary_range)) = memory.requested_ranges {
+                    axes.x.range = x_range;
+                    axes.y.range = y_range;
+                    if let (Some(axis), Some(range)) = (&mut axes.secondary_y, secondary_range) {
+                        axis.range = range;
+                    }
+                }
+                memory.following = true;
+            }
+            ViewRequest::Fit => {}
+        }
+    }
+
+    /// Toggle the scaling of the y axis with L, or of the x axis with shift held. Entering log
+    /// scale clamps the range to positive values.
+    fn toggle_scaling(
+        &self,
+        ui: &Ui,
+        response: &Response,
+        memory: &mut PlotMemory,
+        axes: &mut PlotAxes,
+    ) {
+        if self.allow_scale_toggle
+            && response.hovered()
+            && ui.memory().focus().is_none()
+            && ui.input().key_pressed(Key::L)
+        {
+            let range = if ui.input().modifiers.shift {
+                &mut axes.x.range
+            } else {
+                &mut axes.y.range
+            };
+            range.scaling = if range.is_log() {
+                AxisScaling::Linear
+            } else {
+                AxisScaling::Logarithmic
+            };
+            range.sanitize();
+            memory.scaling = Some((axes.x.range.scaling, axes.y.range.scaling));
+        }
+    }
+
+    /// Adjust the axes to the aspect ratio. Widths are measured in y units, so the same extent
+    /// covers the same distance on screen.
+    fn apply_aspect_ratio(&self, rect: Rect, axes: &mut PlotAxes, zoom_to_requested: bool) {
+        let ratio = match self.aspect_ratio.filter(|_| self.keeps_aspect()) {
+            Some(ratio) => ratio,
+            None => return,
+        };
+        let (painter_width, painter_height) = (rect.width() as f64, rect.height() as f64);
+        let (x_range, y_range) = (&mut axes.x.range, &mut axes.y.range);
+
+        // A requested rectangle has to stay visible, so the dimension that is too small for the
+        // aspect ratio is expanded. The adjustment below then keeps the ranges.
+        if zoom_to_requested {
+            let scale =
+                (x_range.extent() * ratio / painter_width).max(y_range.extent() / painter_height);
+            let x_half = scale * painter_width / ratio / 2.;
+            let y_half = scale * painter_height / 2.;
+            let (x_middle, y_middle) = (x_range.middle(), y_range.middle());
+            x_range.start = x_middle - x_half;
+            x_range.end = x_middle + x_half;
+            y_range.start = y_middle - y_half;
+            y_range.end = y_middle + y_half;
+        }
+
+        let plot_width = x_range.extent() * ratio;
+        let plot_height = y_range.extent();
+        let max_half_extent = plot_width.max(plot_height) / 2.;
+        let painter_ratio = painter_height / painter_width;
+        if painter_ratio > 1. {
+            let x_center = x_range.middle();
+            x_range.start = x_center - max_half_extent / painter_ratio / ratio;
+            x_range.end = x_center + max_half_extent / painter_ratio / ratio;
+        } else {
+            let y_center = y_range.middle();
+            y_range.start = y_center - max_half_extent * painter_ratio;
+            y_range.end = y_center + max_half_extent * painter_ratio;
+        }
+    }
+
+    /// Pan, zoom and select with the pointer, touch gestures and the keyboard. Returns the
+    /// rectangular selection released in this frame and whether it adds to the selection.
+    fn interact(
+        &self,
+        ui: &Ui,
+        response: &Response,
+        area: &PlotArea,
+        memory: &mut PlotMemory,
+        axes: &mut PlotAxes,
+    ) -> Option<(Rect, bool)> {
+        let (drag_pos, released_selection) = self.drag_gestures(ui, response, area, memory, axes);
+        self.drag_pan(ui, area, memory, axes, drag_pos);
+        self.coast(ui, area.rect, memory, axes);
+        self.zoom(ui, response, area, memory, axes);
+        self.keyboard(ui, response, memory, axes);
+        released_selection
+    }
+
+    /// Handle the drags that don't pan: moving points and the legend, measuring, selecting and
+    /// box zooming. Returns the position of a drag that is left for panning, and the rectangular
+    /// selection released in this frame.
+    fn drag_gestures(
+        &self,
+        ui: &Ui,
+        response: &Response,
+        area: &PlotArea,
+        memory: &mut PlotMemory,
+        axes: &mut PlotAxes,
+    ) -> (Option<Pos2>, Option<(Rect, bool)>) {
+        let painter_rect = area.rect;
+
+        // Drags are told apart by where they started. Dragging the tick labels of an axis
+        // only pans that axis, other margins are ignored.
+        let press_origin = ui.input().pointer.press_origin();
+        let pressed_in = |rect: Rect| press_origin.filter(|pos| rect.contains(*pos)).is_some();
+        let mut new_drag_pos = response
+            .interact_pointer_pos()
+            .filter(|_| pressed_in(painter_rect));
+        let mut axis_drag_pos = response
+            .interact_pointer_pos()
+            .filter(|_| pressed_in(area.x_strip) || pressed_in(area.y_strip));
+        let gesture_active =
+            |bindings: &[DragBinding]| bindings.iter().any(|b| b.active(ui.input()));
+
+        // Two finger gestures pan and zoom around the center of the fingers. egui only
+        // reports where the gesture started, so the center is tracked from there. The
+        // pointer follows one of the fingers and must not pan at the same time.
+        let multi_touch = ui.input().multi_touch();
+        memory.touch_centroid = match (&multi_touch, memory.touch_centroid) {
+            (Some(touch), Some(centroid)) => Some(centroid + touch.translation_delta),
+            (Some(touch), None) if painter_rect.contains(touch.start_pos) => {
+                Some(touch.start_pos + touch.translation_delta)
+            }
+            _ => None,
+        };
+        if memory.touch_centroid.is_some() {
+            new_drag_pos = None;
+            axis_drag_pos = None;
+        }
+
+        // Dragging a point, which was found under the pointer in the last frame.
+        if response.drag_started() {
+            memory.grabbed_point = memory.hovered_handle;
+        }
+        if new_drag_pos.is_none() {
+            memory.grabbed_point = None;
+        }
+        if memory.grabbed_point.is_some() {
+            ui.output().cursor_icon = CursorIcon::Grabbing;
+            new_drag_pos = None;
+        }
+
+        // Dragging the legend moves it instead of panning.
+        if response.drag_started() {
+            memory.legend_grab = memory
+                .legend_rect
+                .filter(|rect| pressed_in(*rect))
+                .zip(new_drag_pos)
+                .map(|(rect, pos)| pos - rect.min);
+        }
+        if memory.legend_grab.is_some() {
+            ui.output().cursor_icon = CursorIcon::Grabbing;
+            memory.grabbed_point = None;
+            new_drag_pos = None;
+        }
+
+        // Measuring, by dragging with alt held by default. Releasing the binding or pressing
+        // escape ends it.
+        let measure_held = gesture_active(&self.interaction.measure);
+        if measure_held && response.drag_started() && memory.grabbed_point.is_none() {
+            let transform = ScreenTransform::from_axes(painter_rect, axes.x.range, axes.y.range);
+            memory.measure_anchor = new_drag_pos.map(|pos| transform.value(&pos));
+        }
+        if !measure_held || new_drag_pos.is_none() || ui.input().key_pressed(Key::Escape) {
+            memory.measure_anchor = None;
+        }
+        if measure_held {
+            new_drag_pos = None;
+        }
+
+        // Rectangular selection, by dragging with ctrl held by default.
+        let mut released_selection = None;
+        match (new_drag_pos, memory.selection_rect) {
+            (Some(pos), Some((start, _, additive))) => {
+                memory.selection_rect = Some((start, painter_rect.clamp(pos), additive));
+                new_drag_pos = None;
+            }
+            (Some(pos), None)
+                if self.rect_selection
+                    && gesture_active(&self.interaction.select)
+                    && response.drag_started() =>
+            {
+                let pos = painter_rect.clamp(pos);
+                memory.selection_rect = Some((pos, pos, ui.input().modifiers.shift));
+                new_drag_pos = None;
+            }
+            (None, Some((start, end, additive))) => {
+                memory.selection_rect = None;
+                released_selection = Some((Rect::from_two_pos(start, end), additive));
+            }
+            _ => {}
+        }
+        if ui.input().key_pressed(Key::Escape) {
+            memory.selection_rect = None;
+        }
 
-            // Y-Axis ticks
-            let mut i_start = (y_axis.range.start / increment) as i32;
-            if i_start >= 0 {
-                i_start += 1;
+        // Box zoom, by default by dragging with the secondary button or with shift held. With
+        // an aspect ratio the selection is expanded to it, unless an axis is locked.
+        let box_zoom_held = gesture_active(&self.interaction.box_zoom);
+        match (new_drag_pos, memory.box_zoom) {
+            (Some(pos), Some((start, _))) => {
+                memory.box_zoom = Some((start, painter_rect.clamp(pos)));
+                new_drag_pos = None;
+            }
+            (Some(pos), None) if box_zoom_held && memory.last_drag_pos.is_none() => {
+                let pos = painter_rect.clamp(pos);
+                memory.box_zoom = Some((pos, pos));
+                new_drag_pos = None;
             }
-            loop {
-                let tick_pos_y = i_start as f32 * increment;
-                if tick_pos_y > y_axis.range.end {
-                    break;
+            (None, Some((start, end))) => {
+                memory.box_zoom = None;
+                let mut selection = Rect::from_two_pos(start, end);
+                // Tiny selections are clicks, not zooms.
+                if selection.width() > 4. && selection.height() > 4. {
+                    memory.following = false;
+                    if self.keeps_aspect() {
+                        selection = expand_to_aspect(selection, painter_rect);
+                    }
+                    let x_range = painter_rect.x_range();
+                    let y_range = painter_rect.y_range();
+                    if !self.lock_x {
+                        let range = &mut axes.x.range;
+                        *range = AxisRange {
+                            start: range.pixel_to_axis(x_range.clone(), selection.left(), false),
+                            end: range.pixel_to_axis(x_range, selection.right(), false),
+                            ..*range
+                        };
+                    }
+                    if !self.lock_y {
+                        axes.y_ranges().for_each(|range| {
+                            *range = AxisRange {
+                                start: range.pixel_to_axis(
+                                    y_range.clone(),
+                                    selection.bottom(),
+                                    true,
+                                ),
+                                end: range.pixel_to_axis(y_range.clone(), selection.top(), true),
+                                ..*range
+                            };
+                        });
+                    }
                 }
-                let y_tick = plot_to_screen(&pos2(x_axis.range.start, tick_pos_y));
-                painter.line_segment(
-                    [y_tick, y_tick + 5. * Vec2::X],
-                    Stroke::new(1.0, Color32::WHITE),
-                );
-                painter.line_segment(
-                    [y_tick, y_tick + painter_rect.width() * Vec2::X],
-                    Stroke::new(0.5, Color32::from_white_alpha(5)),
-                );
-                painter.text(
-                    y_tick - 15. * Vec2::X,
-                    Align2::CENTER_CENTER,
-                    format!("{:.1}", tick_pos_y),
-                    TextStyle::Monospace,
-                    Color32::WHITE,
-                );
-                i_start += 1;
             }
+            _ => {}
+        }
 
-            // Restrict painting to the painter area
-            painter.set_clip_rect(painter_rect);
+        (new_drag_pos.or(axis_drag_pos), released_selection)
+    }
 
-            // Call the function provided by the user to add the shapes.
-            let mut plot_ui = PlotUi {
-                painter: &mut painter,
-                plot_to_screen: &plot_to_screen,
-                mouse_position: ui
-                    .input()
-                    .pointer
-                    .interact_pos()
-                    .map(|pos| screen_to_plot(&pos)),
-                hovered: ui
-                    .input()
-                    .pointer
-                    .interact_pos()
-                    .filter(|pos| painter_rect.contains(*pos))
-                    .is_some(),
+    /// Pan with a drag at `drag_pos`. A released drag keeps its velocity, if it was fast enough.
+    fn drag_pan(
+        &self,
+        ui: &Ui,
+        area: &PlotArea,
+        memory: &mut PlotMemory,
+        axes: &mut PlotAxes,
+        drag_pos: Option<Pos2>,
+    ) {
+        let painter_rect = area.rect;
+        let press_origin = ui.input().pointer.press_origin();
+        let pressed_in = |rect: Rect| press_origin.filter(|pos| rect.contains(*pos)).is_some();
+        let pan_x = !self.lock_x && !pressed_in(area.y_strip);
+        let pan_y = !self.lock_y && !pressed_in(area.x_strip);
+        let pan_held = self.interaction.pan.iter().any(|b| b.active(ui.input()));
+
+        let pos = match drag_pos.filter(|_| pan_held) {
+            Some(pos) => pos,
+            None => {
+                if memory.last_drag_pos.is_some() && self.pan_inertia {
+                    let duration: f32 = memory.pan_history.iter().map(|(dt, _)| dt).sum();
+                    let distance = memory
+                        .pan_history
+                        .iter()
+                        .fold(Vec2::ZERO, |sum, (_, pan)| sum + *pan);
+                    memory.pan_velocity = Some(distance / duration).filter(|v| v.is_finite());
+                }
+                memory.pan_history.clear();
+                memory.last_drag_pos = None;
+                return;
+            }
+        };
+
+        // Work in scaled space so panning a logarithmic axis is uniform per decade.
+        let scaled = |pos: Pos2| {
+            Value::new(
+                axes.x
+                    .range
+                    .pixel_to_scaled(painter_rect.x_range(), pos.x, false),
+                axes.y
+                    .range
+                    .pixel_to_scaled(painter_rect.y_range(), pos.y, true),
+            )
+        };
+        if let Some(last_pos) = memory.last_drag_pos {
+            ui.output().cursor_icon = CursorIcon::Grabbing;
+            let (pos_tf, last_pos_tf) = (scaled(pos), scaled(last_pos));
+            let delta = Value::new(last_pos_tf.x - pos_tf.x, last_pos_tf.y - pos_tf.y);
+            if delta != Value::default() {
+                memory.following = false;
+            }
+            if pan_x {
+                axes.x.range.translate(delta.x);
+            }
+            if pan_y {
+                axes.y.range.translate(delta.y);
+            }
+            memory.pan_history.push_back((
+                ui.input().unstable_dt,
+                vec2(
+                    if pan_x { delta.x as f32 } else { 0. },
+                    if pan_y { delta.y as f32 } else { 0. },
+                ),
+            ));
+            if memory.pan_history.len() > PAN_HISTORY {
+                memory.pan_history.pop_front();
+            }
+
+            if let Some(axis) = axes.secondary_y.as_mut().filter(|_| pan_y) {
+                let y_range = painter_rect.y_range();
+                let delta = axis
+                    .range
+                    .pixel_to_scaled(y_range.clone(), last_pos.y, true)
+                    - axis.range.pixel_to_scaled(y_range, pos.y, true);
+                axis.range.translate(delta);
+            }
+        }
+        memory.last_drag_pos = Some(pos);
+    }
+
+    /// Keep moving after a released drag, with a speed that decays independently of the frame
+    /// rate. Any other interaction and reaching the bounds stop it.
+    fn coast(&self, ui: &Ui, rect: Rect, memory: &mut PlotMemory, axes: &mut PlotAxes) {
+        let velocity = match memory.pan_velocity.take() {
+            Some(velocity) => velocity,
+            None => return,
+        };
+        let input = ui.input();
+        let dt = input.unstable_dt;
+        let interrupted = input.pointer.any_down()
+            || input.scroll_delta != Vec2::ZERO
+            || input.zoom_delta() != 1.;
+        let (x_scaled, y_scaled) = (axes.x.range.scaled_range(), axes.y.range.scaled_range());
+        let x_extent = x_scaled.end() - x_scaled.start();
+        let y_extent = y_scaled.end() - y_scaled.start();
+        let pixel_speed = vec2(
+            (velocity.x as f64 / x_extent * rect.width() as f64) as f32,
+            (velocity.y as f64 / y_extent * rect.height() as f64) as f32,
+        )
+        .length();
+        if !interrupted && pixel_speed >= INERTIA_MIN_SPEED {
+            memory.following = false;
+            let step = velocity * dt;
+            axes.x.range.translate(step.x as f64);
+            axes.y.range.translate(step.y as f64);
+            if let Some(axis) = &mut axes.secondary_y {
+                let scaled = axis.range.scaled_range();
+                let extent = scaled.end() - scaled.start();
+                axis.range.translate(step.y as f64 * extent / y_extent);
+            }
+            let at_bounds = self.bounds.filter(|bounds| {
+                let (mut x_range, mut y_range) = (axes.x.range, axes.y.range);
+                x_range.clamp_to(bounds.x_range());
+                y_range.clamp_to(bounds.y_range());
+                x_range != axes.x.range || y_range != axes.y.range
+            });
+            if at_bounds.is_none() {
+                memory.pan_velocity = Some(velocity * (-dt / INERTIA_TIME_CONSTANT).exp());
+                ui.ctx().request_repaint();
+            }
+        }
+    }
+
+    /// Zoom with the scroll wheel and pinch gestures, and pan by scrolling on a trackpad or with
+    /// two fingers. Holding the single axis modifiers or scrolling over an axis zooms only one
+    /// axis, unless the aspect ratio is fixed.
+    fn zoom(
+        &self,
+        ui: &Ui,
+        response: &Response,
+        area: &PlotArea,
+        memory: &mut PlotMemory,
+        axes: &mut PlotAxes,
+    ) {
+        let painter_rect = area.rect;
+        let touch_pan = ui
+            .input()
+            .multi_touch()
+            .filter(|_| memory.touch_centroid.is_some())
+            .map_or(Vec2::ZERO, |touch| touch.translation_delta);
+        let pointer = memory
+            .touch_centroid
+            .or_else(|| ui.input().pointer.interact_pos());
+        let on_strip = |strip: Rect| {
+            self.aspect_ratio.is_none() && pointer.filter(|pos| strip.contains(*pos)).is_some()
+        };
+        let (on_x_strip, on_y_strip) = (on_strip(area.x_strip), on_strip(area.y_strip));
+        if on_x_strip && response.hovered() {
+            ui.output().cursor_icon = CursorIcon::ResizeHorizontal;
+        }
+        if on_y_strip && response.hovered() {
+            ui.output().cursor_icon = CursorIcon::ResizeVertical;
+        }
+        let modifiers = ui.input().modifiers;
+        let held = |required: Modifiers| {
+            required.any() && self.aspect_ratio.is_none() && modifiers_held(required, modifiers)
+        };
+        let zoom_only_x = on_x_strip || (!on_y_strip && held(self.x_zoom_modifiers));
+        let zoom_only_y = on_y_strip || (!zoom_only_x && held(self.y_zoom_modifiers));
+        // Pinch gestures and ctrl+scroll arrive as zoom deltas, which can differ per axis
+        // on touch screens.
+        let zoom_delta = if self.aspect_ratio.is_some() {
+            Vec2::splat(ui.input().zoom_delta())
+        } else {
+            ui.input().zoom_delta_2d()
+        };
+        // The extents are scaled by these, so repeated scrolling compounds.
+        let mut extent_scale = vec2(1. / zoom_delta.x, 1. / zoom_delta.y);
+        let scroll_delta = ui.input().scroll_delta;
+        let on_axis = on_x_strip || on_y_strip;
+        // Horizontal scrolling always pans the x axis.
+        let mut pan_delta = if self.scroll_pans && !on_axis && modifiers.shift {
+            vec2(scroll_delta.x + scroll_delta.y, 0.)
+        } else if self.scroll_pans && !on_axis {
+            scroll_delta
+        } else {
+            let scrolled = if self.invert_zoom {
+                scroll_delta.y
+            } else {
+                -scroll_delta.y
             };
-            add_contents(&mut plot_ui);
+            extent_scale *= self.zoom_speed.powf(scrolled);
+            vec2(scroll_delta.x, 0.)
+        };
+        pan_delta += touch_pan;
+        let zoom_factor = if self.allow_zoom {
+            extent_scale - Vec2::splat(1.)
+        } else {
+            Vec2::ZERO
+        };
+        let mouse_pos = match pointer.filter(|pos| painter_rect.contains(*pos) || on_axis) {
+            Some(pos) => pos,
+            None => return,
+        };
 
-            // Show mouse position
-            if show_cursor_pos {
-                if let Some(mouse_pos) = ui
-                    .input()
-                    .pointer
-                    .interact_pos()
-                    .filter(|pos| painter_rect.contains(*pos))
-                {
-                    let mouse_pos = screen_to_plot(&mouse_pos);
-                    painter.text(
-                        painter_rect.right_bottom() + vec2(-10., -10.),
-                        Align2::RIGHT_BOTTOM,
-                        format!("{:?}", mouse_pos),
-                        TextStyle::Monospace,
-                        Color32::WHITE,
+        if zoom_factor != Vec2::ZERO {
+            memory.following = false;
+            let left_distance = (mouse_pos.x - painter_rect.left()) / painter_rect.width();
+            let bottom_distance = (painter_rect.bottom() - mouse_pos.y) / painter_rect.height();
+            let (x_zoom, y_zoom) = (zoom_factor.x as f64, zoom_factor.y as f64);
+            let (x_center, y_center) = (left_distance as f64, bottom_distance as f64);
+            if !self.lock_x && !zoom_only_y {
+                axes.x
+                    .range
+                    .zoom_limited(x_zoom, x_center, self.x_limits.clone());
+            }
+            if !self.lock_y && !zoom_only_x {
+                axes.y_ranges()
+                    .for_each(|range| range.zoom_limited(y_zoom, y_center, self.y_limits.clone()));
+            }
+        }
+
+        // Scrolling moves the content with the fingers on a trackpad or touch screen.
+        if pan_delta != Vec2::ZERO && self.allow_drag {
+            memory.following = false;
+            let pan = vec2(
+                -pan_delta.x / painter_rect.width(),
+                pan_delta.y / painter_rect.height(),
+            )
+            .clamp(Vec2::splat(-0.5), Vec2::splat(0.5));
+            if !self.lock_x {
+                let scaled = axes.x.range.scaled_range();
+                axes.x
+                    .range
+                    .translate(pan.x as f64 * (scaled.end() - scaled.start()));
+            }
+            if !self.lock_y {
+                axes.y_ranges().for_each(|range| {
+                    let scaled = range.scaled_range();
+                    range.translate(pan.y as f64 * (scaled.end() - scaled.start()));
+                });
+            }
+        }
+    }
+
+    /// Pan with the arrow keys, zoom with page up and down or plus and minus, and go back to the
+    /// requested view with home. Only while hovered and no other widget has keyboard focus.
+    fn keyboard(&self, ui: &Ui, response: &Response, memory: &mut PlotMemory, axes: &mut PlotAxes) {
+        if !self.allow_keyboard || !response.hovered() || ui.memory().focus().is_some() {
+            return;
+        }
+        let input = ui.input();
+        let step: f64 = if input.modifiers.shift { 0.02 } else { 0.1 };
+        let mut pan = Value::default();
+        if input.key_pressed(Key::ArrowLeft) {
+            pan.x -= step;
+        }
+        if input.key_pressed(Key::ArrowRight) {
+            pan.x += step;
+        }
+        if input.key_pressed(Key::ArrowDown) {
+            pan.y -= step;
+        }
+        if input.key_pressed(Key::ArrowUp) {
+            pan.y += step;
+        }
+        let mut zoom = 0.;
+        if input.key_pressed(Key::PageUp) {
+            zoom -= step;
+        }
+        if input.key_pressed(Key::PageDown) {
+            zoom += step;
+        }
+        input.events.iter().for_each(|event| match event {
+            Event::Text(text) if text == "+" || text == "=" => zoom -= step,
+            Event::Text(text) if text == "-" => zoom += step,
+            _ => {}
+        });
+        if !self.allow_zoom {
+            zoom = 0.;
+        }
+        if pan != Value::default() || zoom != 0. {
+            memory.following = false;
+        }
+
+        // Pan by a fraction of the visible extent, in scaled space.
+        if !self.lock_x {
+            let scaled = axes.x.range.scaled_range();
+            axes.x
+                .range
+                .translate(pan.x * (scaled.end() - scaled.start()));
+            axes.x.range.zoom_limited(zoom, 0.5, self.x_limits.clone());
+        }
+        if !self.lock_y {
+            axes.y_ranges().for_each(|range| {
+                let scaled = range.scaled_range();
+                range.translate(pan.y * (scaled.end() - scaled.start()));
+                range.zoom_limited(zoom, 0.5, self.y_limits.clone());
+            });
+        }
+
+        if input.key_pressed(Key::Home) {
+            if let Some((x_range, y_range, secondary_range)) = memory.requested_ranges {
+                axes.x.range = AxisRange {
+                    scaling: axes.x.range.scaling,
+                    ..x_range
+                };
+                axes.y.range = AxisRange {
+                    scaling: axes.y.range.scaling,
+                    ..y_range
+                };
+                if let (Some(axis), Some(requested)) = (&mut axes.secondary_y, secondary_range) {
+                    axis.range = requested;
+                }
+            }
+            memory.following = true;
+        }
+    }
+}
+
+/// How the axes around the plot area are laid out and painted, from the settings of the `Plot`.
+struct AxisStyle {
+    top_x_axis: Option<TopAxis>,
+    y_tick_side: Side,
+    margins: Margins,
+    log_minor_ticks: bool,
+    x_time: Option<TimeFormat>,
+    tick_marks: TickMarks,
+    tick_text_style: TextStyle,
+    axis_label_text_style: TextStyle,
+    tick_labels_inside: bool,
+    x_tick_label_overrides: Vec<(f64, String)>,
+    x_tick_override_color: Option<Color32>,
+    show_axes_lines: bool,
+    axes_lines_stroke: Option<Stroke>,
+}
+
+/// The plot area and the axes the shapes of the axes are laid out for, see `AxisStyle::shapes`.
+struct TickLayout<'a> {
+    painter: &'a Painter,
+    rect: Rect,
+    transform: ScreenTransform,
+    axes: &'a PlotAxes,
+    text_color: Color32,
+}
+
+impl AxisStyle {
+    /// Distance of the tick labels from the plot area.
+    fn label_offset(&self) -> f32 {
+        LABEL_GAP + self.tick_marks.outward_length()
+    }
+
+    /// Whether the y tick labels are on the left and on the right. A secondary axis takes up the
+    /// right side.
+    fn y_tick_sides(&self, axes: &PlotAxes) -> (bool, bool) {
+        (
+            self.y_tick_side != Side::Right,
+            self.y_tick_side != Side::Left && axes.secondary_y.is_none(),
+        )
+    }
+
+    /// The height of the tick labels below the plot area.
+    fn x_labels_height(&self, painter: &Painter) -> f32 {
+        if self.tick_labels_inside {
+            LABEL_GAP
+        } else {
+            self.label_offset() + LABEL_GAP + painter.fonts().row_height(self.tick_text_style)
+        }
+    }
+
+    /// Room above the plot area for the labels of a transformed top axis.
+    fn top_labels_height(&self, painter: &Painter) -> f32 {
+        match self.top_x_axis {
+            Some(TopAxis::Transformed { .. }) => {
+                self.label_offset() + painter.fonts().row_height(self.tick_text_style)
+            }
+            _ => self.tick_marks.outward_length(),
+        }
+    }
+
+    /// Place the plot area within `full_rect`. The margins are sized to fit the labels around
+    /// it, and a title of the given height.
+    fn layout(
+        &self,
+        painter: &Painter,
+        full_rect: Rect,
+        axes: &PlotAxes,
+        title_height: Option<f32>,
+    ) -> PlotArea {
+        let label_row_height = painter.fonts().row_height(self.axis_label_text_style);
+        let label_offset = self.label_offset();
+        // The plot area isn't known yet, its full height is close enough to choose the ticks.
+        let y_labels_width = max_label_width(
+            painter,
+            &axes.y.ticks(full_rect.height(), self.log_minor_ticks, None),
+            self.tick_text_style,
+        );
+        let (y_ticks_left, y_ticks_right) = self.y_tick_sides(axes);
+        let mut left_margin = if self.tick_labels_inside || !y_ticks_left {
+            LABEL_GAP
+        } else {
+            label_offset + LABEL_GAP + y_labels_width
+        };
+        if !axes.y.label.is_empty() {
+            left_margin += label_row_height + 2.;
+        }
+        let mut bottom_margin = self.x_labels_height(painter);
+        if !axes.x.label.is_empty() {
+            bottom_margin += label_row_height + LABEL_GAP;
+        }
+        let mut right_margin = 0.;
+        if y_ticks_right && !self.tick_labels_inside {
+            right_margin += label_offset + LABEL_GAP + y_labels_width;
+        }
+        if let Some(axis) = &axes.secondary_y {
+            right_margin += label_offset
+                + LABEL_GAP
+                + max_label_width(
+                    painter,
+                    &axis.ticks(full_rect.height(), self.log_minor_ticks, None),
+                    self.tick_text_style,
+                );
+            if !axis.label.is_empty() {
+                right_margin += label_row_height + 2.;
+            }
+        }
+        let mut top_margin = self.top_labels_height(painter);
+        if let Some(height) = title_height {
+            top_margin += height + 2.;
+        }
+        let left_margin = left_margin.max(self.margins.left);
+        let right_margin = right_margin.max(self.margins.right);
+        let top_margin = top_margin.max(self.margins.top);
+        let bottom_margin = bottom_margin.max(self.margins.bottom);
+
+        let rect = Rect::from_min_max(
+            full_rect.min + vec2(left_margin, top_margin),
+            full_rect.max - vec2(right_margin, bottom_margin),
+        );
+        let x_strip =
+            Rect::from_min_max(rect.left_bottom(), pos2(rect.right(), full_rect.bottom()));
+        let y_strip = if y_ticks_left {
+            Rect::from_min_max(pos2(full_rect.left(), rect.top()), rect.left_bottom())
+        } else {
+            Rect::from_min_max(rect.right_top(), pos2(full_rect.right(), rect.bottom()))
+        };
+        PlotArea {
+            full: full_rect,
+            rect,
+            x_strip,
+            y_strip,
+        }
+    }
+
+    /// Paint the labels of the axes, outside of their tick labels.
+    fn paint_labels(&self, painter: &Painter, area: &PlotArea, axes: &PlotAxes, color: Color32) {
+        if !axes.x.label.is_empty() {
+            painter.text(
+                area.rect.center_bottom() + vec2(0., self.x_labels_height(painter)),
+                Align2::CENTER_TOP,
+                axes.x.label.clone(),
+                self.axis_label_text_style,
+                color,
+            );
+        }
+
+        if !axes.y.label.is_empty() {
+            paint_vertical_label(
+                painter,
+                &axes.y.label,
+                area.full.left() + 2.,
+                area.rect.y_range(),
+                self.axis_label_text_style,
+                color,
+            );
+        }
+
+        if let Some(axis) = axes.secondary_y.as_ref().filter(|a| !a.label.is_empty()) {
+            let label_row_height = painter.fonts().row_height(self.axis_label_text_style);
+            paint_vertical_label(
+                painter,
+                &axis.label,
+                area.full.right() - label_row_height - 2.,
+                area.rect.y_range(),
+                self.axis_label_text_style,
+                color,
+            );
+        }
+    }
+
+    /// Lay out the grid, the tick marks and the tick labels of all axes, and the lines through
+    /// the origin.
+    fn shapes(&self, layout: &TickLayout) -> AxisShapes {
+        // The tick marks and the labels outside the plot area.
+        let mut labels = PlotPainter::new(layout.painter, Vec::new(), Vec::new());
+        let mut shapes = AxisShapes {
+            grid: Vec::new(),
+            axes_lines: self.axes_lines(layout),
+            labels: Vec::new(),
+            inside_labels: Vec::new(),
+        };
+        self.x_ticks(layout, &mut labels, &mut shapes);
+        self.y_ticks(layout, &mut labels, &mut shapes);
+        self.top_ticks(layout, &mut labels);
+        self.secondary_y_ticks(layout, &mut labels);
+        shapes.labels = labels.into_parts().0;
+        shapes
+    }
+
+    /// Ticks along the bottom edge. Only every `stride`-th label is shown if they would
+    /// overlap, and automatic labels give way to overrides they would overlap.
+    fn x_ticks(&self, layout: &TickLayout, labels: &mut PlotPainter, shapes: &mut AxisShapes) {
+        let TickLayout {
+            painter,
+            rect,
+            transform,
+            axes,
+            text_color,
+        } = *layout;
+        let label_offset = self.label_offset();
+        let x_ticks = axes
+            .x
+            .ticks(rect.width(), self.log_minor_ticks, self.x_time);
+        let label_extents: Vec<(f32, f32)> = x_ticks
+            .iter()
+            .filter_map(|tick| {
+                let label = tick.label.clone()?;
+                let x = transform
+                    .position(&Value::new(tick.value, axes.y.range.start))
+                    .x;
+                let width = painter
+                    .layout_no_wrap(label, self.tick_text_style, text_color)
+                    .size()
+                    .x;
+                Some((x, width))
+            })
+            .collect();
+        let stride = label_stride(&label_extents, LABEL_GAP);
+        let mut label_index = 0;
+        let override_color = self.x_tick_override_color.unwrap_or(text_color);
+        let overrides: Vec<(Pos2, f32, String)> = self
+            .x_tick_label_overrides
+            .iter()
+            .filter(|(value, _)| axes.x.range.start <= *value && *value <= axes.x.range.end)
+            .map(|(value, label)| {
+                let x_tick = transform.position(&Value::new(*value, axes.y.range.start));
+                let width = painter
+                    .layout_no_wrap(label.clone(), self.tick_text_style, override_color)
+                    .size()
+                    .x;
+                (x_tick, width, label.clone())
+            })
+            .collect();
+        for tick in x_ticks {
+            let x_tick = transform.position(&Value::new(tick.value, axes.y.range.start));
+            let grid_alpha = if tick.label.is_some() { 5 } else { 2 };
+            self.tick_marks
+                .paint(labels, x_tick, -Vec2::Y, tick.label.is_some(), text_color);
+            shapes.grid.push(Shape::line_segment(
+                [x_tick, x_tick - rect.height() * Vec2::Y],
+                Stroke::new(0.5, text_color.linear_multiply(grid_alpha as f32 / 255.)),
+            ));
+            if let Some(label) = tick.label {
+                let width = label_extents[label_index].1;
+                let show_label = label_index % stride == 0
+                    && overrides.iter().all(|(pos, other_width, _)| {
+                        (pos.x - x_tick.x).abs() >= (width + other_width) / 2. + LABEL_GAP
+                    });
+                if show_label && self.tick_labels_inside {
+                    shapes
+                        .inside_labels
+                        .push((x_tick + vec2(2., -2.), label, text_color));
+                } else if show_label {
+                    labels.text(
+                        x_tick + label_offset * Vec2::Y,
+                        Align2::CENTER_TOP,
+                        label,
+                        self.tick_text_style,
+                        text_color,
                     );
                 }
+                label_index += 1;
             }
+        }
 
-            *x_axis_range = x_axis.range;
-            *y_axis_range = y_axis.range;
+        for (x_tick, _, label) in overrides {
+            self.tick_marks
+                .paint(labels, x_tick, -Vec2::Y, true, override_color);
+            if self.tick_labels_inside {
+                shapes
+                    .inside_labels
+                    .push((x_tick + vec2(2., -2.), label, override_color));
+            } else {
+                labels.text(
+                    x_tick + label_offset * Vec2::Y,
+                    Align2::CENTER_TOP,
+                    label,
+                    self.tick_text_style,
+                    override_color,
+                );
+            }
+        }
+    }
 
-            response
-        })
+    /// Ticks along the left or right edge, or both.
+    fn y_ticks(&self, layout: &TickLayout, labels: &mut PlotPainter, shapes: &mut AxisShapes) {
+        let TickLayout {
+            rect,
+            transform,
+            axes,
+            text_color,
+            ..
+        } = *layout;
+        let label_offset = self.label_offset();
+        let (y_ticks_left, y_ticks_right) = self.y_tick_sides(axes);
+        for tick in axes.y.ticks(rect.height(), self.log_minor_ticks, None) {
+            let y_tick = transform.position(&Value::new(axes.x.range.start, tick.value));
+            let y_tick_right = pos2(rect.right(), y_tick.y);
+            let major = tick.label.is_some();
+            let grid_alpha = if major { 5 } else { 2 };
+            if y_ticks_left {
+                self.tick_marks
+                    .paint(labels, y_tick, Vec2::X, major, text_color);
+            }
+            if y_ticks_right {
+                self.tick_marks
+                    .paint(labels, y_tick_right, -Vec2::X, major, text_color);
+            }
+            shapes.grid.push(Shape::line_segment(
+                [y_tick, y_tick + rect.width() * Vec2::X],
+                Stroke::new(0.5, text_color.linear_multiply(grid_alpha as f32 / 255.)),
+            ));
+            if let Some(label) = tick.label {
+                if self.tick_labels_inside {
+                    shapes
+                        .inside_labels
+                        .push((y_tick + vec2(2., -2.), label, text_color));
+                    continue;
+                }
+                if y_ticks_right {
+                    labels.text(
+                        y_tick_right + label_offset * Vec2::X,
+                        Align2::LEFT_CENTER,
+                        label.clone(),
+                        self.tick_text_style,
+                        text_color,
+                    );
+                }
+                if y_ticks_left {
+                    labels.text(
+                        y_tick - label_offset * Vec2::X,
+                        Align2::RIGHT_CENTER,
+                        label,
+                        self.tick_text_style,
+                        text_color,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Ticks along the top edge, mirroring the bottom ones or on a transformed scale.
+    fn top_ticks(&self, layout: &TickLayout, labels: &mut PlotPainter) {
+        let TickLayout {
+            rect,
+            transform,
+            axes,
+            text_color,
+            ..
+        } = *layout;
+        let mut top_labeled_ticks = Vec::new();
+        match &self.top_x_axis {
+            Some(TopAxis::Mirror) => {
+                let ticks = axes
+                    .x
+                    .ticks(rect.width(), self.log_minor_ticks, self.x_time);
+                for tick in ticks {
+                    let x_tick = transform.position(&Value::new(tick.value, axes.y.range.end));
+                    self.tick_marks.paint(
+                        labels,
+                        x_tick,
+                        Vec2::Y,
+                        tick.label.is_some(),
+                        text_color,
+                    );
+                }
+            }
+            Some(TopAxis::Transformed {
+                to_top,
+                from_top,
+                formatter,
+            }) => {
+                // Choose nice values on the top scale, then find where they are.
+                top_labeled_ticks = top_ticks(
+                    &axes.x.range,
+                    rect.width(),
+                    to_top.as_ref(),
+                    &|value| match from_top {
+                        Some(from_top) => Some(from_top(value)),
+                        None => invert_monotonic(to_top.as_ref(), value, &axes.x.range),
+                    },
+                    formatter.as_deref(),
+                );
+            }
+            None => {}
+        }
+        for (x, label) in top_labeled_ticks {
+            let x_tick = transform.position(&Value::new(x, axes.y.range.end));
+            self.tick_marks
+                .paint(labels, x_tick, Vec2::Y, true, text_color);
+            labels.text(
+                x_tick - self.label_offset() * Vec2::Y,
+                Align2::CENTER_BOTTOM,
+                label,
+                self.tick_text_style,
+                text_color,
+            );
+        }
+    }
+
+    /// Ticks of the secondary y axis, along the right edge.
+    fn secondary_y_ticks(&self, layout: &TickLayout, labels: &mut PlotPainter) {
+        let TickLayout {
+            rect,
+            axes,
+            text_color,
+            ..
+        } = *layout;
+        let axis = match &axes.secondary_y {
+            Some(axis) => axis,
+            None => return,
+        };
+        for tick in axis.ticks(rect.height(), self.log_minor_ticks, None) {
+            let y_tick = pos2(
+                rect.right(),
+                axis.range.axis_to_pixel(rect.y_range(), tick.value, true),
+            );
+            self.tick_marks
+                .paint(labels, y_tick, -Vec2::X, tick.label.is_some(), text_color);
+            if let Some(label) = tick.label {
+                labels.text(
+                    y_tick + self.label_offset() * Vec2::X,
+                    Align2::LEFT_CENTER,
+                    label,
+                    self.tick_text_style,
+                    text_color,
+                );
+            }
+        }
+    }
+
+    /// Lines through the origin, if it is visible.
+    fn axes_lines(&self, layout: &TickLayout) -> Vec<Shape> {
+        let TickLayout {
+            rect,
+            transform,
+            axes,
+            text_color,
+            ..
+        } = *layout;
+        let mut axes_lines = Vec::new();
+        if !self.show_axes_lines {
+            return axes_lines;
+        }
+        let stroke = self
+            .axes_lines_stroke
+            .unwrap_or_else(|| Stroke::new(1.0, text_color.linear_multiply(0.3)));
+        if axes.x.range.start <= 0. && axes.x.range.end >= 0. {
+            let x = transform.position(&Value::new(0., axes.y.range.start)).x;
+            axes_lines.push(Shape::line_segment(
+                [pos2(x, rect.top()), pos2(x, rect.bottom())],
+                stroke,
+            ));
+        }
+        if axes.y.range.start <= 0. && axes.y.range.end >= 0. {
+            let y = transform.position(&Value::new(axes.x.range.start, 0.)).y;
+            axes_lines.push(Shape::line_segment(
+                [pos2(rect.left(), y), pos2(rect.right(), y)],
+                stroke,
+            ));
+        }
+        axes_lines
+    }
+}
+
+/// Paints the markers, readouts and the legend on top of the items.
+struct Overlay<'a> {
+    painter: &'a Painter,
+    /// The plot area, which the readouts are kept inside of.
+    rect: Rect,
+    transform: ScreenTransform,
+    axes: &'a PlotAxes,
+    text_style: TextStyle,
+    text_color: Color32,
+    background_color: Color32,
+    frame_stroke: Stroke,
+}
+
+impl<'a> Overlay<'a> {
+    /// Paint the distance and the slope from `anchor` to the pointer.
+    fn measurement(&self, anchor: Value, pointer: Pos2) {
+        let (painter, painter_rect) = (self.painter, self.rect);
+        let start = self.transform.position(&anchor);
+        let end = painter_rect.clamp(pointer);
+        let end_value = self.transform.value(&end);
+        let delta = Value::new(end_value.x - anchor.x, end_value.y - anchor.y);
+        let slope = if delta.x == 0. {
+            "∞".to_string()
+        } else {
+            format_significant(delta.y / delta.x, 3)
+        };
+        let text = format!(
+            "Δx: {}\nΔy: {}\ndistance: {}\nslope: {}",
+            self.axes.x.format_value(delta.x),
+            self.axes.y.format_value(delta.y),
+            format_significant(delta.x.hypot(delta.y), 3),
+            slope
+        );
+        let stroke = Stroke::new(1., self.text_color);
+        painter.line_segment([start, end], stroke);
+        painter.circle_filled(start, 2., self.text_color);
+        painter.circle_filled(end, 2., self.text_color);
+        let galley = painter.layout_no_wrap(text, self.text_style, self.text_color);
+        let mut rect = Rect::from_min_size(end + vec2(8., 8.), galley.size());
+        rect = rect.translate((painter_rect.max - rect.max).min(Vec2::ZERO));
+        painter.rect(
+            rect.expand(2.),
+            2.,
+            self.background_color,
+            self.frame_stroke,
+        );
+        painter.galley(rect.min, galley);
+    }
+
+    /// Paint tick labels inside the plot area, on a translucent background.
+    fn inside_labels(&self, labels: Vec<(Pos2, String, Color32)>) {
+        for (pos, label, color) in labels {
+            let galley = self.painter.layout_no_wrap(label, self.text_style, color);
+            let rect = Rect::from_min_size(pos - vec2(0., galley.size().y), galley.size());
+            self.painter.rect_filled(
+                rect.expand(1.),
+                2.,
+                self.background_color.linear_multiply(0.7),
+            );
+            self.painter.galley(rect.min, galley);
+        }
+    }
+
+    /// Paint a crosshair through `pos`, with its coordinates along the edges of the plot area.
+    fn crosshair(&self, pos: Pos2, stroke: Stroke) {
+        let (painter, painter_rect) = (self.painter, self.rect);
+        painter.line_segment(
+            [
+                pos2(painter_rect.left(), pos.y),
+                pos2(painter_rect.right(), pos.y),
+            ],
+            stroke,
+        );
+        painter.line_segment(
+            [
+                pos2(pos.x, painter_rect.top()),
+                pos2(pos.x, painter_rect.bottom()),
+            ],
+            stroke,
+        );
+        let value = self.transform.value(&pos);
+        let labels = vec![
+            (
+                pos2(pos.x, painter_rect.bottom()),
+                Align2::CENTER_BOTTOM,
+                self.axes.x.format_value(value.x),
+            ),
+            (
+                pos2(painter_rect.left(), pos.y),
+                Align2::LEFT_CENTER,
+                self.axes.y.format_value(value.y),
+            ),
+        ];
+        for (anchor, align, label) in labels {
+            let galley = painter.layout_no_wrap(label, self.text_style, self.text_color);
+            let mut rect = align.anchor_rect(Rect::from_min_size(anchor, galley.size()));
+            // Keep the label inside the plot area.
+            rect = rect.translate((painter_rect.min - rect.min).max(Vec2::ZERO));
+            rect = rect.translate((painter_rect.max - rect.max).min(Vec2::ZERO));
+            painter.rect(rect.expand(2.), 2., self.background_color, stroke);
+            painter.galley(rect.min, galley);
+        }
+    }
+
+    /// Paint the values of the named items next to a vertical line at their x value, in a panel
+    /// starting at the height `top`.
+    fn readout(&self, readout: HoverReadout, top: f32) {
+        let (painter, painter_rect) = (self.painter, self.rect);
+        let x = self.transform.position(&Value::new(readout.x, 0.)).x;
+        let stroke = Stroke::new(1., self.text_color.linear_multiply(0.5));
+        painter.line_segment(
+            [pos2(x, painter_rect.top()), pos2(x, painter_rect.bottom())],
+            stroke,
+        );
+        let header = painter.layout_no_wrap(
+            self.axes.x.format_value(readout.x),
+            self.text_style,
+            self.text_color,
+        );
+        let rows: Vec<_> = readout
+            .rows
+            .into_iter()
+            .map(|(name, color, value)| {
+                let value = value.map_or_else(|| "—".to_string(), |y| self.axes.y.format_value(y));
+                let text = format!("{}: {}", name, value);
+                (
+                    color,
+                    painter.layout_no_wrap(text, self.text_style, self.text_color),
+                )
+            })
+            .collect();
+        let swatch = header.size().y;
+        let width = rows
+            .iter()
+            .map(|(_, galley)| swatch + 4. + galley.size().x)
+            .fold(header.size().x, f32::max);
+        let height = rows
+            .iter()
+            .map(|(_, galley)| galley.size().y)
+            .fold(header.size().y, |sum, h| sum + h);
+        let mut rect = Rect::from_min_size(pos2(x + 8., top), vec2(width, height));
+        // Flip to the left of the line and keep the panel inside the plot area.
+        if rect.right() > painter_rect.right() {
+            rect = rect.translate(vec2(-16. - rect.width(), 0.));
+        }
+        rect = rect.translate((painter_rect.max - rect.max).min(Vec2::ZERO));
+        rect = rect.translate((painter_rect.min - rect.min).max(Vec2::ZERO));
+        painter.rect(
+            rect.expand(2.),
+            2.,
+            self.background_color,
+            self.frame_stroke,
+        );
+        let mut y = rect.top() + header.size().y;
+        painter.galley(rect.min, header);
+        for (color, galley) in rows {
+            let swatch_rect =
+                Rect::from_min_size(pos2(rect.left(), y), vec2(swatch, swatch)).shrink(2.);
+            painter.rect_filled(swatch_rect, 1., color);
+            let height = galley.size().y;
+            painter.galley(pos2(rect.left() + swatch + 4., y), galley);
+            y += height;
+        }
     }
 
-    fn pixels_to_plot(
-        pixel_pos: &Pos2,
-        x_pixel_range: &AxisRange,
-        y_pixel_range: &AxisRange,
-        plot_rect: &Rect,
-    ) -> Pos2 {
-        let x_tf = x_pixel_range.pixel_to_axis(plot_rect.x_range(), pixel_pos.x, false);
-        let y_tf = y_pixel_range.pixel_to_axis(plot_rect.y_range(), pixel_pos.y, true);
-        pos2(x_tf, y_tf)
+    /// Paint the legend. Clicking an entry hides or shows the item, hovering highlights it.
+    /// It can be dragged around and snaps to the corners when released close to one.
+    fn legend(
+        &self,
+        ui: &Ui,
+        response: &Response,
+        entries: &[(String, Color32)],
+        memory: &mut PlotMemory,
+    ) {
+        let painter_rect = self.rect;
+        let legend = Legend {
+            entries,
+            hidden_items: &memory.hidden_items,
+            text_style: self.text_style,
+            text_color: self.text_color,
+            background_color: self.background_color.linear_multiply(0.8),
+            frame_stroke: self.frame_stroke,
+        };
+        let size = legend.size(self.painter);
+        if let Some(grab) = memory.legend_grab {
+            let mut rect = place_legend(memory.legend_position, size, painter_rect);
+            if let Some(pos) = response.interact_pointer_pos() {
+                rect = Rect::from_min_size(pos - grab, size);
+            }
+            memory.legend_position = (Align2::LEFT_TOP, rect.min - painter_rect.min);
+            if !response.dragged() {
+                let rect = place_legend(memory.legend_position, size, painter_rect);
+                memory.legend_position = snap_legend(rect, painter_rect);
+                memory.legend_grab = None;
+            }
+        }
+        let rect = place_legend(memory.legend_position, size, painter_rect);
+        memory.legend_rect = Some(rect);
+        let hovered_entry = legend.paint(self.painter, rect, ui.input().pointer.hover_pos());
+        if let Some(name) = hovered_entry {
+            if response.clicked() && !memory.hidden_items.remove(&name) {
+                memory.hidden_items.insert(name.clone());
+            }
+            memory.highlighted_item = Some(name);
+        }
     }
 
-    fn plot_to_pixels(
-        plot_pos: &Pos2,
-        x_plot_range: &AxisRange,
-        y_plot_range: &AxisRange,
-        plot_rect: &Rect,
-    ) -> Pos2 {
-        let x_tf = x_plot_range.axis_to_pixel(plot_rect.x_range(), plot_pos.x, false);
-        let y_tf = y_plot_range.axis_to_pixel(plot_rect.y_range(), plot_pos.y, true);
-        pos2(x_tf, y_tf)
+    /// Paint the coordinates and the name of the data point closest to the pointer.
+    fn tooltip(&self, nearest: &NearestPoint) {
+        let (painter, painter_rect) = (self.painter, self.rect);
+        painter.circle_stroke(nearest.screen_pos, 4., Stroke::new(1., self.text_color));
+        let mut text = format!(
+            "{}, {}",
+            self.axes.x.format_value(nearest.value.x),
+            self.axes.y.format_value(nearest.value.y)
+        );
+        if let Some(name) = &nearest.name {
+            text = format!("{}\n{}", name, text);
+        }
+        let galley = painter.layout_no_wrap(text, self.text_style, self.text_color);
+        let mut rect = Rect::from_min_size(
+            nearest.screen_pos + vec2(8., -8. - galley.size().y),
+            galley.size(),
+        );
+        // Keep the tooltip inside the plot area.
+        if rect.right() > painter_rect.right() {
+            rect = rect.translate(vec2(-16. - rect.width(), 0.));
+        }
+        if rect.top() < painter_rect.top() {
+            rect = rect.translate(vec2(0., 16. + rect.height()));
+        }
+        painter.rect(
+            rect.expand(2.),
+            2.,
+            self.background_color,
+            self.frame_stroke,
+        );
+        painter.galley(rect.min, galley);
     }
 }
+
+/// Show the toolbar at `pos`, layered over the plot so its buttons take precedence over
+/// dragging. Returns the action of a clicked button.
+fn toolbar(ui: &Ui, id: Id, pos: Pos2, allow_zoom: bool) -> Option<PlotAction> {
+    let mut action = None;
+    Area::new(id.with("toolbar"))
+        .order(Order::Foreground)
+        .fixed_pos(pos)
+        .show(ui.ctx(), |ui| {
+            Frame::popup(ui.style()).show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    let mut buttons = vec![
+                        ("🏠", "Reset view", PlotAction::ResetView),
+                        ("🔍", "Fit to data", PlotAction::AutoFit),
+                    ];
+                    if allow_zoom {
+                        buttons.push(("+", "Zoom in", PlotAction::ZoomIn));
+                        buttons.push(("-", "Zoom out", PlotAction::ZoomOut));
+                    }
+                    buttons.push(("#", "Toggle grid", PlotAction::ToggleGrid));
+                    for (icon, hover_text, button) in buttons {
+                        if ui.small_button(icon).on_hover_text(hover_text).clicked() {
+                            action = Some(button);
+                        }
+                    }
+                });
+            });
+        });
+    action
+}
+
+/// Show the context menu at `screen_pos`, followed by the custom entries, which are passed the
+/// position in plot coordinates. Returns the action of a clicked built-in entry.
+fn context_menu(
+    ui: &Ui,
+    id: Id,
+    (screen_pos, plot_pos): (Pos2, Value),
+    custom_entries: Option<ContextMenuEntries<'_>>,
+) -> Option<PlotAction> {
+    let mut action = None;
+    Area::new(id.with("context_menu"))
+        .order(Order::Foreground)
+        .fixed_pos(screen_pos)
+        .show(ui.ctx(), |ui| {
+            Frame::popup(ui.style()).show(ui, |ui| {
+                let entries = vec![
+                    ("Reset view", PlotAction::ResetView),
+                    ("Auto-fit", PlotAction::AutoFit),
+                    ("Toggle grid", PlotAction::ToggleGrid),
+                    ("Copy cursor position", PlotAction::CopyPosition(plot_pos)),
+                    ("Copy view range", PlotAction::CopyView),
+                ];
+                for (label, entry) in entries {
+                    if ui.button(label).clicked() {
+                        action = Some(entry);
+                    }
+                }
+                if let Some(entries) = custom_entries {
+                    ui.separator();
+                    entries(ui, plot_pos);
+                }
+            });
+        });
+    action
+}
@@ -0,0 +1,93 @@
+use std::collections::VecDeque;
+
+use super::items::{Line, Value};
+
+/// A series of streaming samples that drops the oldest ones, e.g. for live plots together with
+/// `Plot::follow_x`. Pushing a sample never reallocates once the series is full.
+#[derive(Clone, Debug)]
+pub struct RollingSeries {
+    points: VecDeque<Value>,
+    capacity: usize,
+    max_x_window: Option<f64>,
+}
+
+impl RollingSeries {
+    /// Keeps at most the last `capacity` samples.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            points: VecDeque::with_capacity(capacity),
+            capacity,
+            max_x_window: None,
+        }
+    }
+
+    /// Also drop the samples that are more than `window` behind the latest x value.
+    pub fn max_x_window(mut self, window: f64) -> Self {
+        self.max_x_window = Some(window);
+        self
+    }
+
+    /// Append a sample, evicting the oldest ones that no longer fit.
+    pub fn push(&mut self, x: f64, y: f64) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.points.len() == self.capacity {
+            self.points.pop_front();
+        }
+        self.points.push_back(Value::new(x, y));
+        if let Some(window) = self.max_x_window {
+            while let Some(oldest) = self.points.front() {
+                if oldest.x >= x - window {
+                    break;
+                }
+                self.points.pop_front();
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.points.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The most recent sample.
+    pub fn last(&self) -> Option<Value> {
+        self.points.back().copied()
+    }
+
+    /// The samples from oldest to newest.
+    pub fn iter(&self) -> impl Iterator<Item = &Value> {
+        self.points.iter()
+    }
+
+    /// The samples as the two contiguous parts of the ring buffer. The first part holds the
+    /// oldest samples, the second one continues where the first one ends.
+    pub fn as_slices(&self) -> (&[Value], &[Value]) {
+        self.points.as_slices()
+    }
+
+    /// The samples from oldest to newest as a single slice, rotating the ring buffer if needed.
+    pub fn make_contiguous(&mut self) -> &[Value] {
+        self.points.make_contiguous()
+    }
+}
+
+/// Borrows the samples after rotating the ring buffer into one contiguous slice, which moves
+/// them within the buffer but never allocates.
+impl<'a> From<&'a mut RollingSeries> for Line<'a> {
+    fn from(series: &'a mut RollingSeries) -> Self {
+        Line::new(series.make_contiguous())
+    }
+}
@@ -0,0 +1,81 @@
+//! Painting the same items again reuses the buffers of the previous frame. This needs its own
+//! test binary, since the allocator counts the allocations of the whole process.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use eframe::egui::{pos2, vec2, CentralPanel, Color32, CtxRef, RawInput, Rect, Stroke};
+use eplot::{
+    items::{Line, Scatter, Value},
+    plot::PlotCtx,
+};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Run one frame with a plot of `points`, if any, and count the allocations of the plot.
+fn frame(ctx: &mut CtxRef, plot_ctx: &mut PlotCtx, points: Option<&[Value]>) -> usize {
+    let mut allocations = 0;
+    ctx.begin_frame(RawInput {
+        screen_rect: Some(Rect::from_min_size(pos2(0., 0.), vec2(1920., 1080.))),
+        ..Default::default()
+    });
+    CentralPanel::default().show(ctx, |ui| {
+        let before = ALLOCATIONS.load(Ordering::Relaxed);
+        plot_ctx
+            .plot("allocations")
+            .size(vec2(1600., 900.))
+            .x_axis_range(0.0..=100.)
+            .y_axis_range(-1.5..=1.5)
+            .show(ui, |plot_ui| {
+                if let Some(points) = points {
+                    plot_ui.add(Line::new(points));
+                    plot_ui.add(Scatter::new(points).stroke(Stroke::new(1., Color32::RED)));
+                }
+            });
+        allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    });
+    let _ = ctx.end_frame();
+    allocations
+}
+
+#[test]
+fn second_frame_allocates_less() {
+    let (mut ctx, mut plot_ctx) = (CtxRef::default(), PlotCtx::default());
+    let points: Vec<Value> = (0..10_000)
+        .map(|i| {
+            let x = i as f64 / 100.;
+            Value::new(x, x.sin())
+        })
+        .collect();
+    // Set up egui and the plot memory, so only the items make a difference.
+    frame(&mut ctx, &mut plot_ctx, None);
+    let first = frame(&mut ctx, &mut plot_ctx, Some(&points));
+    let second = frame(&mut ctx, &mut plot_ctx, Some(&points));
+    assert!(
+        second < first,
+        "the second frame allocated {} times, the first one {} times",
+        second,
+        first
+    );
+}
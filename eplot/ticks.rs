@@ -0,0 +1,483 @@
+use eframe::egui::emath;
+
+use super::plot::{AxisRange, LogLabels, TickFormat, TickSpacing};
+use super::time::{time_ticks, TimeFormat};
+
+/// Upper limit for the number of ticks on an axis, so tiny increments can't stall the frame.
+pub(crate) const MAX_TICKS: usize = 200;
+
+/// Ticks closer than this, in points, would blur into a solid band.
+const MIN_TICK_DISTANCE: f32 = 2.;
+
+/// A tick on an axis. Only major ticks have a label.
+pub(crate) struct Tick {
+    pub(crate) value: f64,
+    pub(crate) label: Option<String>,
+}
+
+impl Tick {
+    pub(crate) fn major(value: f64, label: String) -> Self {
+        Self {
+            value,
+            label: Some(label),
+        }
+    }
+
+    fn minor(value: f64) -> Self {
+        Self { value, label: None }
+    }
+
+    pub(crate) fn is_major(&self) -> bool {
+        self.label.is_some()
+    }
+}
+
+/// Everything besides the range that decides where the ticks of an axis go and how they are
+/// labeled.
+#[derive(Clone, Copy)]
+pub(crate) struct TickConfig<'a> {
+    pub(crate) spacing: TickSpacing,
+    pub(crate) format: &'a TickFormat,
+    /// Label the ticks with times instead of numbers.
+    pub(crate) time: Option<TimeFormat>,
+    /// Label the ticks with SI prefixes and this unit.
+    pub(crate) si_unit: Option<&'a str>,
+    pub(crate) log_labels: LogLabels,
+    /// Ticks between the decades of a logarithmic axis.
+    pub(crate) minor_ticks: bool,
+}
+
+/// The ticks within `range`, which is `pixels` long on screen, in increasing order.
+pub(crate) fn generate_ticks(range: &AxisRange, pixels: f32, config: &TickConfig) -> Vec<Tick> {
+    let TickConfig {
+        spacing,
+        format,
+        time,
+        si_unit,
+        log_labels,
+        minor_ticks,
+    } = *config;
+    let max_ticks = max_ticks(pixels);
+    if let Some(time) = time {
+        return time_axis_ticks(range, spacing, time);
+    }
+    match format {
+        TickFormat::Pi => ticks_with(range, spacing, max_ticks, pi_increment, format_pi),
+        TickFormat::Percent { decimals } => {
+            ticks_with(range, spacing, max_ticks, percent_increment, |value| {
+                format_percent(value, *decimals)
+            })
+        }
+        TickFormat::Custom(formatter) => {
+            let mut ticks = numeric_ticks(range, spacing, max_ticks, minor_ticks, log_labels);
+            ticks
+                .iter_mut()
+                .filter(|tick| tick.is_major())
+                .for_each(|tick| tick.label = Some(formatter(tick.value)));
+            ticks
+        }
+        TickFormat::Auto => {
+            let mut ticks = numeric_ticks(range, spacing, max_ticks, minor_ticks, log_labels);
+            if let Some(unit) = si_unit {
+                let increment = range.increment(spacing);
+                let magnitude = range.magnitude();
+                ticks.iter_mut().for_each(|tick| {
+                    tick.label = Some(format_si(tick.value, increment, magnitude, unit));
+                });
+            }
+            ticks
+        }
+    }
+}
+
+/// How many ticks fit on an axis that is `pixels` long.
+fn max_ticks(pixels: f32) -> usize {
+    ((pixels / MIN_TICK_DISTANCE) as usize).min(MAX_TICKS)
+}
+
+/// Whether ticks every `increment` over `extent` can be shown. After extreme zooming, or with a
+/// degenerate range, the increment can end up zero or NaN, or there would be far too many ticks.
+/// The ticks and the grid are left out entirely then, instead of showing only some of them.
+fn within_tick_limit(extent: f64, increment: f64, max_ticks: usize) -> bool {
+    increment.is_finite() && increment > 0. && (extent / increment).abs() <= max_ticks as f64
+}
+
+/// Linear axes are divided according to `spacing`, logarithmic axes get ticks at every decade.
+/// If fewer than two decades are visible, a logarithmic axis gets linear ticks instead.
+fn numeric_ticks(
+    range: &AxisRange,
+    spacing: TickSpacing,
+    max_ticks: usize,
+    minor_ticks: bool,
+    log_labels: LogLabels,
+) -> Vec<Tick> {
+    let mut ticks = Vec::new();
+    if spacing == TickSpacing::None {
+        return ticks;
+    }
+    let increment = range.increment(spacing);
+    if range.is_log() {
+        let first_decade = range.start.log10().floor();
+        let last_decade = range.end.log10().ceil();
+        if !within_tick_limit(last_decade - first_decade, 1., max_ticks) {
+            return ticks;
+        }
+        // Leave out the minor ticks rather than all of them if there would be too many.
+        let minor_ticks =
+            minor_ticks && within_tick_limit(9. * (last_decade - first_decade), 1., max_ticks);
+        (first_decade as i32..=last_decade as i32).for_each(|decade| {
+            let base = 10f64.powi(decade);
+            ticks.push(Tick::major(base, log_labels.format(decade)));
+            if minor_ticks {
+                (2..=9).for_each(|m| ticks.push(Tick::minor(m as f64 * base)));
+            }
+        });
+        ticks.retain(|tick| tick.value >= range.start && tick.value <= range.end);
+        if ticks.iter().filter(|tick| tick.is_major()).count() >= 2 {
+            return ticks;
+        }
+        ticks.clear();
+    }
+    if !within_tick_limit(range.extent(), increment, max_ticks) {
+        return ticks;
+    }
+    let magnitude = range.magnitude();
+    let mut first = (range.start / increment).trunc();
+    if first >= 0. {
+        first += 1.;
+    }
+    // Counting in floats can't overflow. Far from zero, consecutive multiples of the
+    // increment might not be representable, the limit keeps that from looping forever.
+    (0..max_ticks)
+        .map(|i| (first + i as f64) * increment)
+        .take_while(|tick_pos| *tick_pos <= range.end)
+        .for_each(|tick_pos| {
+            ticks.push(Tick::major(
+                tick_pos,
+                format_tick(tick_pos, increment, magnitude),
+            ))
+        });
+    ticks
+}
+
+/// Ticks at multiples of an increment chosen by `auto_increment`, labeled with `format`.
+fn ticks_with(
+    range: &AxisRange,
+    spacing: TickSpacing,
+    max_ticks: usize,
+    auto_increment: impl Fn(f64, usize) -> f64,
+    format: impl Fn(f64) -> String,
+) -> Vec<Tick> {
+    let increment = match spacing {
+        TickSpacing::Auto { target_count } => auto_increment(range.extent(), target_count),
+        TickSpacing::Fixed(increment) => increment,
+        TickSpacing::None => return Vec::new(),
+    };
+    multiples(range.start, range.end, increment, max_ticks)
+        .into_iter()
+        .map(|value| Tick::major(value, format(value)))
+        .collect()
+}
+
+/// The multiples of `increment` within `start..=end`, or none if there would be more than
+/// `max_ticks`. Each value is computed from its index, so the values still advance where adding
+/// the increment to a large value wouldn't change it.
+pub(crate) fn multiples(start: f64, end: f64, increment: f64, max_ticks: usize) -> Vec<f64> {
+    if !within_tick_limit(end - start, increment, max_ticks) {
+        return Vec::new();
+    }
+    let first = (start / increment).ceil() as i64;
+    let last = (end / increment).floor() as i64;
+    (first..=last)
+        .take(max_ticks)
+        .map(|i| i as f64 * increment)
+        .collect()
+}
+
+/// Ticks for an axis showing time in seconds.
+fn time_axis_ticks(range: &AxisRange, spacing: TickSpacing, format: TimeFormat) -> Vec<Tick> {
+    let max_ticks = match spacing {
+        TickSpacing::Auto { target_count } => target_count + target_count / 2,
+        TickSpacing::Fixed(_) => 8,
+        TickSpacing::None => return Vec::new(),
+    };
+    time_ticks(range.start, range.end, max_ticks, format)
+        .into_iter()
+        .map(|(value, label)| Tick::major(value, label))
+        .collect()
+}
+
+/// A round increment that divides `extent` into roughly `tick_count` steps.
+pub(crate) fn tick_increment(extent: f64, tick_count: usize) -> f64 {
+    let rough_increment = extent / tick_count as f64;
+    emath::smart_aim::best_in_range_f64(rough_increment * 0.5, rough_increment * 1.5)
+}
+
+/// Format a tick label with just enough decimals to tell neighboring ticks apart. Values that
+/// are very large or increments that are very small switch to scientific notation, which keeps
+/// the labels short enough to fit in the margins.
+pub(crate) fn format_tick(value: f64, increment: f64, magnitude: f64) -> String {
+    if value == 0. {
+        return "0".to_string();
+    }
+    let increment_exponent = increment.abs().log10().floor();
+    if magnitude >= 1e6 || increment.abs() < 1e-4 {
+        let digits = (magnitude.log10().floor() - increment_exponent).clamp(0., 6.) as usize;
+        format!("{:.*e}", digits, value)
+    } else {
+        let decimals = (-increment.abs().log10()).ceil().max(0.) as usize;
+        format!("{:.*}", decimals, value)
+    }
+}
+
+/// Format a value with a number of significant digits.
+pub(crate) fn format_significant(value: f64, digits: i32) -> String {
+    let increment = 10f64.powf(value.abs().log10().floor() - (digits - 1) as f64);
+    format_tick(value, increment, value.abs())
+}
+
+/// An increment of π times a power of two, or π times a round number for large extents.
+pub(crate) fn pi_increment(extent: f64, tick_count: usize) -> f64 {
+    let rough = extent / tick_count as f64 / std::f64::consts::PI;
+    let units = if rough >= 1. {
+        tick_increment(extent / std::f64::consts::PI, tick_count)
+    } else {
+        2f64.powf(rough.log2().round().max(-6.))
+    };
+    units * std::f64::consts::PI
+}
+
+/// An increment of 1, 2.5 or 5 times a power of ten percent.
+fn percent_increment(extent: f64, tick_count: usize) -> f64 {
+    let rough = 100. * extent / tick_count as f64;
+    let power = 10f64.powf(rough.log10().floor());
+    let step = [1., 2.5, 5., 10.]
+        .iter()
+        .map(|factor| factor * power)
+        .min_by(|a, b| {
+            (a.ln() - rough.ln())
+                .abs()
+                .partial_cmp(&(b.ln() - rough.ln()).abs())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+        .unwrap_or(power);
+    step / 100.
+}
+
+pub(crate) fn format_percent(value: f64, decimals: usize) -> String {
+    format!("{:.*}%", decimals, 100. * value)
+}
+
+/// Format a value as a small rational multiple of π, or as a decimal if there is none.
+fn format_pi(value: f64) -> String {
+    let multiple = value / std::f64::consts::PI;
+    if multiple.abs() < 1e-6 {
+        return "0".to_string();
+    }
+    let fraction = [1, 2, 3, 4, 6, 8, 12, 16, 32, 64]
+        .iter()
+        .map(|&denominator| (multiple * denominator as f64, denominator))
+        .find(|(numerator, _)| (numerator - numerator.round()).abs() < 1e-3);
+    match fraction {
+        Some((numerator, denominator)) => {
+            let numerator = numerator.round() as i64;
+            let sign = if numerator < 0 { "-" } else { "" };
+            let coefficient = match numerator.abs() {
+                1 => "".to_string(),
+                n => n.to_string(),
+            };
+            if denominator == 1 {
+                format!("{}{}π", sign, coefficient)
+            } else {
+                format!("{}{}π/{}", sign, coefficient, denominator)
+            }
+        }
+        None => format!("{:.3}", value),
+    }
+}
+
+/// Format a value with an SI prefix chosen from `magnitude`, so all ticks of an axis share it.
+pub(crate) fn format_si(value: f64, increment: f64, magnitude: f64, unit: &str) -> String {
+    const PREFIXES: [&str; 9] = ["p", "n", "µ", "m", "", "k", "M", "G", "T"];
+    let group = if magnitude > 0. && magnitude.is_finite() {
+        ((magnitude.log10() / 3.).floor() as i32).clamp(-4, 4)
+    } else {
+        0
+    };
+    let scale = 10f64.powi(3 * group);
+    let decimals = (-(increment.abs() / scale).log10()).ceil().max(0.) as usize;
+    format!(
+        "{:.*} {}{}",
+        decimals,
+        value / scale,
+        PREFIXES[(group + 4) as usize],
+        unit
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticks(range: &AxisRange, spacing: TickSpacing) -> Vec<Tick> {
+        let config = TickConfig {
+            spacing,
+            format: &TickFormat::Auto,
+            time: None,
+            si_unit: None,
+            log_labels: LogLabels::Plain,
+            minor_ticks: false,
+        };
+        generate_ticks(range, 1000., &config)
+    }
+
+    fn values(ticks: &[Tick]) -> Vec<f64> {
+        ticks.iter().map(|tick| tick.value).collect()
+    }
+
+    fn labels(ticks: &[Tick]) -> Vec<&str> {
+        ticks
+            .iter()
+            .filter_map(|tick| tick.label.as_deref())
+            .collect()
+    }
+
+    const AUTO: TickSpacing = TickSpacing::Auto { target_count: 5 };
+
+    #[test]
+    fn increment_follows_zoom() {
+        [1e-6, 1e-3, 0.1, 1., 10., 1e3, 1e6, 1e12]
+            .iter()
+            .for_each(|extent| {
+                let increment = tick_increment(*extent, 5);
+                assert!(
+                    increment >= extent / 10. && increment <= extent / 2.5,
+                    "increment {} for extent {}",
+                    increment,
+                    extent
+                );
+                let mantissa = increment / 10f64.powf(increment.log10().floor());
+                assert!(
+                    [1., 2., 5.].iter().any(|m| (mantissa - m).abs() < 1e-9),
+                    "increment {} is not round",
+                    increment
+                );
+            });
+        let zoomed_out = ticks(&AxisRange::new(-15.0..=35.), AUTO);
+        assert_eq!(values(&zoomed_out), vec![-10., 0., 10., 20., 30.]);
+        assert_eq!(labels(&zoomed_out), vec!["-10", "0", "10", "20", "30"]);
+        let zoomed_in = ticks(&AxisRange::new(0.135..=0.185), AUTO);
+        assert_eq!(
+            labels(&zoomed_in),
+            vec!["0.14", "0.15", "0.16", "0.17", "0.18"]
+        );
+    }
+
+    #[test]
+    fn degenerate_ranges_have_no_ticks() {
+        [
+            AxisRange::new(1.0..=1.),
+            AxisRange::new(f64::NAN..=1.),
+            AxisRange::new(0.0..=f64::NAN),
+            AxisRange::new(10.0..=0.),
+        ]
+        .iter()
+        .for_each(|range| {
+            assert!(ticks(range, AUTO).is_empty());
+            assert!(ticks(range, TickSpacing::Fixed(1.)).is_empty());
+        });
+        assert!(ticks(&AxisRange::new(0.0..=1.), TickSpacing::Fixed(0.)).is_empty());
+        assert!(ticks(&AxisRange::new(0.0..=1.), TickSpacing::Fixed(f64::NAN)).is_empty());
+    }
+
+    #[test]
+    fn far_from_zero_the_tick_limit_ends_the_loop() {
+        // Around 1e15, f64 can't represent every multiple of these increments, so the counter
+        // gets stuck on the same value.
+        [
+            (AxisRange::new(1e15..=1e15 + 1.), TickSpacing::Fixed(0.01)),
+            (
+                AxisRange::new(1e15..=1e15 + 0.125),
+                TickSpacing::Fixed(1e-3),
+            ),
+            (
+                AxisRange::new(-1e15 - 1.0..=-1e15),
+                TickSpacing::Fixed(0.01),
+            ),
+            (AxisRange::new(1e15..=1e15 + 1.), AUTO),
+        ]
+        .iter()
+        .for_each(|(range, spacing)| {
+            let ticks = ticks(range, *spacing);
+            assert!(ticks.len() <= MAX_TICKS);
+            assert!(ticks
+                .iter()
+                .all(|tick| tick.value >= range.start && tick.value <= range.end));
+        });
+        assert_eq!(max_ticks(1e9), MAX_TICKS);
+        assert_eq!(max_ticks(100.), 50);
+    }
+
+    #[test]
+    fn multiples_of_a_large_magnitude() {
+        // The ulp of 1e8 in f32 is 8, adding an increment of 2 wouldn't change the value.
+        assert_eq!(
+            multiples(1e8, 1e8 + 8., 2., MAX_TICKS),
+            vec![1e8, 1e8 + 2., 1e8 + 4., 1e8 + 6., 1e8 + 8.]
+        );
+        assert_eq!(multiples(-1.5, 1.5, 1., MAX_TICKS), vec![-1., 0., 1.]);
+        assert!(multiples(1e15, 1e15 + 1., 1e-3, MAX_TICKS).is_empty());
+        assert!(multiples(1., 0., 0.25, MAX_TICKS).is_empty());
+        assert!(multiples(0., f64::NAN, 0.25, MAX_TICKS).is_empty());
+    }
+
+    #[test]
+    fn log_axis_ticks() {
+        let decades = ticks(&AxisRange::logarithmic(1.0..=1000.), AUTO);
+        assert_eq!(values(&decades), vec![1., 10., 100., 1000.]);
+        assert_eq!(labels(&decades), vec!["1", "10", "100", "1000"]);
+        // Only one decade tick would be visible.
+        let linear = ticks(&AxisRange::logarithmic(2.0..=50.), AUTO);
+        assert_eq!(values(&linear), vec![10., 20., 30., 40., 50.]);
+    }
+
+    #[test]
+    fn format_tick_switches_to_scientific() {
+        assert_eq!(format_tick(0., 1., 10.), "0");
+        assert_eq!(format_tick(0.25, 0.05, 1.), "0.25");
+        assert_eq!(format_tick(1234.5, 0.1, 1e4), "1234.5");
+        assert_eq!(format_tick(-40., 20., 100.), "-40");
+        assert_eq!(format_tick(2e6, 5e5, 2e6), "2.0e6");
+        assert_eq!(format_tick(1.5e-4, 5e-5, 1e-3), "1.50e-4");
+        assert_eq!(format_significant(0.012345, 2), "0.012");
+    }
+
+    #[test]
+    fn pi_labels() {
+        use std::f64::consts::PI;
+        assert_eq!(format_pi(0.), "0");
+        assert_eq!(format_pi(PI), "π");
+        assert_eq!(format_pi(-PI / 2.), "-π/2");
+        assert_eq!(format_pi(3. * PI / 4.), "3π/4");
+        assert_eq!(format_pi(4. * PI), "4π");
+        assert_eq!(format_pi(1.), "1.000");
+        assert!((pi_increment(2. * PI, 4) - PI / 2.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn percent_labels() {
+        assert!((percent_increment(1., 5) - 0.25).abs() < 1e-12);
+        assert!((percent_increment(0.1, 5) - 0.025).abs() < 1e-12);
+        assert!((percent_increment(0.05, 5) - 0.01).abs() < 1e-12);
+        assert_eq!(format_percent(0.25, 0), "25%");
+        assert_eq!(format_percent(0.025, 1), "2.5%");
+    }
+
+    #[test]
+    fn si_labels() {
+        assert_eq!(format_si(1500., 500., 2000., "Hz"), "1.5 kHz");
+        assert_eq!(format_si(0.002, 0.001, 0.005, "s"), "2 ms");
+        assert_eq!(format_si(3e9, 1e9, 5e9, "B"), "3 GB");
+        assert_eq!(format_si(0., 1., 0., "V"), "0 V");
+    }
+}
@@ -0,0 +1,267 @@
+/// How time values on an axis are interpreted.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimeFormat {
+    /// Seconds since the unix epoch (UTC). Day boundaries are labeled with the date.
+    Epoch,
+    /// Seconds since an arbitrary start. Day boundaries are labeled with the day count.
+    Elapsed,
+}
+
+const MINUTE: f64 = 60.;
+const HOUR: f64 = 60. * MINUTE;
+const DAY: f64 = 24. * HOUR;
+
+/// Epoch times beyond this many seconds, about 30 million years, aren't labeled as dates.
+const MAX_DATE: f64 = 1e15;
+
+/// Month steps for the ticks of epoch axes spanning months or more.
+const NICE_MONTH_STEPS: [i64; 11] = [1, 2, 3, 6, 12, 24, 60, 120, 240, 600, 1200];
+
+/// Tick increments in seconds that line up with clock and calendar boundaries. On an elapsed
+/// time axis, the last two steps count 30 and 365 days. Epoch axes use calendar months and years
+/// instead, see `calendar_ticks`.
+const NICE_TIME_STEPS: [f64; 31] = [
+    0.001,
+    0.002,
+    0.005,
+    0.01,
+    0.02,
+    0.05,
+    0.1,
+    0.2,
+    0.5,
+    1.,
+    2.,
+    5.,
+    10.,
+    15.,
+    30.,
+    MINUTE,
+    2. * MINUTE,
+    5. * MINUTE,
+    10. * MINUTE,
+    15. * MINUTE,
+    30. * MINUTE,
+    HOUR,
+    2. * HOUR,
+    3. * HOUR,
+    6. * HOUR,
+    12. * HOUR,
+    DAY,
+    2. * DAY,
+    7. * DAY,
+    30. * DAY,
+    365. * DAY,
+];
+
+/// Ticks for a time axis spanning `start..=end` seconds, together with their labels.
+/// The math is done in f64 so that epoch timestamps keep sub-second resolution.
+pub(crate) fn time_ticks(
+    start: f64,
+    end: f64,
+    max_ticks: usize,
+    format: TimeFormat,
+) -> Vec<(f64, String)> {
+    let extent = end - start;
+    if !extent.is_finite() || extent <= 0. {
+        return Vec::new();
+    }
+    let step = NICE_TIME_STEPS
+        .iter()
+        .copied()
+        .find(|step| extent / step <= max_ticks as f64)
+        .unwrap_or_else(|| {
+            let years = (extent / (365. * DAY) / max_ticks as f64).ceil();
+            years * 365. * DAY
+        });
+    if format == TimeFormat::Epoch && step >= 30. * DAY && start.abs().max(end.abs()) < MAX_DATE {
+        return calendar_ticks(start, end, max_ticks);
+    }
+
+    let mut ticks = Vec::new();
+    let mut i = (start / step).ceil();
+    while i * step <= end && ticks.len() <= max_ticks {
+        let value = i * step;
+        ticks.push((value, time_label(value, step, format)));
+        i += 1.;
+    }
+    ticks
+}
+
+/// Ticks on the first day of every few months or years, for epoch axes spanning months or more.
+fn calendar_ticks(start: f64, end: f64, max_ticks: usize) -> Vec<(f64, String)> {
+    const MONTH: f64 = 365.2425 / 12. * DAY;
+    let extent = end - start;
+    let months = NICE_MONTH_STEPS
+        .iter()
+        .copied()
+        .find(|months| extent / (*months as f64 * MONTH) <= max_ticks as f64)
+        .unwrap_or_else(|| 12 * (extent / (12. * MONTH) / max_ticks as f64).ceil() as i64);
+    // Count months from year zero, so multi-month steps start at the same months every year.
+    let (year, month, _) = civil_from_days((start / DAY).floor() as i64);
+    let first = (12 * year + month as i64 - 1).div_euclid(months) * months;
+    (0..)
+        .map(|i| {
+            let index = first + i * months;
+            let (year, month) = (index.div_euclid(12), index.rem_euclid(12) as u32 + 1);
+            let value = days_from_civil(year, month, 1) as f64 * DAY;
+            let label = if months < 12 {
+                format!("{:04}-{:02}", year, month)
+            } else {
+                format!("{:04}", year)
+            };
+            (value, label)
+        })
+        .skip_while(|(value, _)| *value < start)
+        .take_while(|(value, _)| *value <= end)
+        .take(max_ticks + 1)
+        .collect()
+}
+
+fn time_label(value: f64, step: f64, format: TimeFormat) -> String {
+    let days = (value / DAY).floor();
+    let seconds_of_day = value - days * DAY;
+    let at_day_boundary = seconds_of_day.abs() < step * 1e-3;
+
+    if step >= DAY || at_day_boundary {
+        return match format {
+            TimeFormat::Epoch => {
+                let (year, month, day) = civil_from_days(days as i64);
+                format!("{:04}-{:02}-{:02}", year, month, day)
+            }
+            TimeFormat::Elapsed => format!("{}d", days),
+        };
+    }
+
+    let hours = (seconds_of_day / HOUR).floor();
+    let minutes = ((seconds_of_day - hours * HOUR) / MINUTE).floor();
+    let seconds = seconds_of_day - hours * HOUR - minutes * MINUTE;
+    if step < 1. {
+        let decimals = (-step.log10()).ceil() as usize;
+        format!(
+            "{:02}:{:0width$.decimals$}",
+            minutes,
+            seconds,
+            width = decimals + 3,
+            decimals = decimals
+        )
+    } else if step < MINUTE {
+        format!("{:02}:{:02}:{:02}", hours, minutes, seconds.round())
+    } else {
+        format!("{:02}:{:02}", hours, minutes)
+    }
+}
+
+/// Convert a date in the proleptic Gregorian calendar to days since 1970-01-01.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let month = month as i64;
+    let doy = (153 * if month > 2 { month - 3 } else { month + 9 } + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Convert days since 1970-01-01 to a (year, month, day) date in the proleptic Gregorian calendar.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(ticks: &[(f64, String)]) -> Vec<&str> {
+        ticks.iter().map(|(_, label)| label.as_str()).collect()
+    }
+
+    #[test]
+    fn dates_from_days() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(11_017), (2000, 3, 1));
+        assert_eq!(civil_from_days(-719_468), (0, 3, 1));
+        (-800_000..800_000).step_by(97).for_each(|days| {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days);
+        });
+    }
+
+    #[test]
+    fn day_boundaries_show_the_date() {
+        let epoch = time_ticks(0., 2. * DAY, 10, TimeFormat::Epoch);
+        assert_eq!(
+            labels(&epoch),
+            vec![
+                "1970-01-01",
+                "06:00",
+                "12:00",
+                "18:00",
+                "1970-01-02",
+                "06:00",
+                "12:00",
+                "18:00",
+                "1970-01-03"
+            ]
+        );
+        let elapsed = time_ticks(-DAY / 2., DAY / 2., 4, TimeFormat::Elapsed);
+        assert_eq!(
+            labels(&elapsed),
+            vec!["12:00", "18:00", "0d", "06:00", "12:00"]
+        );
+    }
+
+    #[test]
+    fn sub_second_labels() {
+        let ticks = time_ticks(61., 61.2, 5, TimeFormat::Elapsed);
+        assert_eq!(
+            labels(&ticks),
+            vec!["01:01.00", "01:01.05", "01:01.10", "01:01.15", "01:01.20"]
+        );
+        let ticks = time_ticks(59.98, 60.02, 4, TimeFormat::Elapsed);
+        assert_eq!(labels(&ticks), vec!["00:59.98", "01:00.00", "01:00.02"]);
+    }
+
+    #[test]
+    fn epoch_months_and_years_start_on_the_first() {
+        let start = days_from_civil(2021, 1, 15) as f64 * DAY;
+        let end = days_from_civil(2021, 12, 31) as f64 * DAY;
+        let ticks = time_ticks(start, end, 12, TimeFormat::Epoch);
+        assert_eq!(ticks.len(), 11);
+        assert_eq!(
+            ticks[0],
+            (
+                days_from_civil(2021, 2, 1) as f64 * DAY,
+                "2021-02".to_string()
+            )
+        );
+        assert_eq!(ticks[10].1, "2021-12");
+
+        let start = days_from_civil(2000, 6, 1) as f64 * DAY;
+        let end = days_from_civil(2019, 6, 1) as f64 * DAY;
+        let ticks = time_ticks(start, end, 10, TimeFormat::Epoch);
+        assert_eq!(
+            labels(&ticks),
+            vec!["2002", "2004", "2006", "2008", "2010", "2012", "2014", "2016", "2018"]
+        );
+        assert_eq!(ticks[0].0, days_from_civil(2002, 1, 1) as f64 * DAY);
+    }
+
+    #[test]
+    fn degenerate_ranges_have_no_ticks() {
+        assert!(time_ticks(5., 5., 10, TimeFormat::Epoch).is_empty());
+        assert!(time_ticks(5., 1., 10, TimeFormat::Epoch).is_empty());
+        assert!(time_ticks(f64::NAN, 1., 10, TimeFormat::Elapsed).is_empty());
+    }
+}
@@ -1,12 +1,16 @@
 use eframe::{egui::*, epi};
 use eplot::{
-    items::{Line, MarkerShape, Polygon, Quiver, Scatter, Text, YReference},
-    plot::PlotCtx,
+    items::{
+        Bezier, Callout, Colormap, HLine, Line, MarkerShape, Polygon, Quiver, Scatter, Text,
+        YReference,
+    },
+    plot::{Axis, PlotCtx},
 };
 
 pub struct TemplateApp {
     start_time: std::time::Instant,
     plot_ctx: PlotCtx,
+    threshold: f64,
 }
 
 impl Default for TemplateApp {
@@ -14,6 +18,7 @@ impl Default for TemplateApp {
         Self {
             start_time: std::time::Instant::now(),
             plot_ctx: PlotCtx::default(),
+            threshold: -8.,
         }
     }
 }
@@ -25,6 +30,7 @@ impl epi::App for TemplateApp {
         let Self {
             plot_ctx,
             start_time,
+            threshold,
         } = self;
 
         let t = std::time::Instant::now()
@@ -32,12 +38,17 @@ impl epi::App for TemplateApp {
             .as_secs_f32();
 
         CentralPanel::default().show(ctx, |ui| {
-            plot_ctx
+            let response = plot_ctx
                 .plot("TestPlot")
                 .title("eplot showcase")
                 .size(vec2(1280., 720.))
-                .x_axis_range(-10f32..=10.)
-                .y_axis_range(-10f32..=10.)
+                .x_axis_range(-10.0..=10.)
+                .y_axis_range(-10.0..=10.)
+                .axis_equal(true)
+                .x_axis_label("x")
+                .y_axis_label("y")
+                .secondary_y_axis(Axis::new(0.0..=100.).label("percent"))
+                .hover_tooltip(true)
                 .show(ui, |plot_ui| {
                     // Line
                     [4., 3., 2., 1., 0.5]
@@ -63,10 +74,24 @@ impl epi::App for TemplateApp {
                         })
                         .collect();
                     plot_ui.add(
-                        Line::new(points).color(Color32::GOLD).area_fill(
-                            YReference::Constant(0.),
-                            Color32::GOLD.linear_multiply(0.1),
-                        ),
+                        Line::new(points)
+                            .name("filled")
+                            .color(Color32::GOLD)
+                            .area_fill(YReference::Constant(0.), Color32::GOLD.linear_multiply(0.1))
+                            .expect("a constant reference fits any number of points"),
+                    );
+
+                    // Line on the secondary axis
+                    let points: Vec<Pos2> = (-200..=200)
+                        .map(|i| {
+                            let x = i as f32 / 20.;
+                            pos2(x, 50. + 40. * (x + t).cos())
+                        })
+                        .collect();
+                    plot_ui.add_secondary(
+                        Line::new(points)
+                            .name("secondary")
+                            .color(Color32::LIGHT_RED),
                     );
 
                     // Scatter
@@ -79,11 +104,14 @@ impl epi::App for TemplateApp {
                         .collect();
                     plot_ui.add(
                         Scatter::new(points)
+                            .name("scatter")
+                            .interactable("scatter")
                             .fill_color(Color32::RED)
                             .size(3.)
                             .stroke(Stroke::new(1., Color32::RED))
                             .shape(MarkerShape::Circle)
-                            .stems(YReference::Constant(0.), Stroke::new(1., Color32::WHITE)),
+                            .stems(YReference::Constant(0.), Stroke::new(1., Color32::WHITE))
+                            .expect("a constant reference fits any number of points"),
                     );
 
                     // Arrow polygon
@@ -104,6 +132,7 @@ impl epi::App for TemplateApp {
                                 Color32::from_rgba_unmultiplied(255, 0, 255, 255),
                             )),
                     );
+                    plot_ui.add(Callout::new(pos2(2., -4.), "Arrow tip").offset(vec2(30., 20.)));
 
                     // Scatter markers
                     let markers_position = pos2(7., -3.);
@@ -150,6 +179,7 @@ impl epi::App for TemplateApp {
                     let mut directions = Vec::new();
                     let maybe_mouse_pos = plot_ui
                         .plot_mouse_position()
+                        .map(|value| value.to_pos2())
                         .filter(|_| plot_ui.plot_hovered())
                         .filter(|pos| {
                             Rect::from_center_size(center, vec2(11., 11.)).contains(*pos)
@@ -163,14 +193,49 @@ impl epi::App for TemplateApp {
                             directions.push(-1. / dir.length().max(1.) * dir.normalized());
                         });
                     });
-                    plot_ui.add(Quiver::new(points, directions));
+                    plot_ui.add(
+                        Quiver::new(points, directions)
+                            .color_by_magnitude(None, Colormap::viridis()),
+                    );
+
+                    // Bezier
+                    let wobble = vec2(0., (2. * t).sin());
+                    plot_ui.add(
+                        Bezier::new(vec![
+                            [
+                                pos2(8., 5.),
+                                pos2(9., 9.) + wobble,
+                                pos2(11., 5.) - wobble,
+                                pos2(12., 7.),
+                            ],
+                            [
+                                pos2(12., 7.),
+                                pos2(13., 9.),
+                                pos2(15., 9.) + wobble,
+                                pos2(16., 6.),
+                            ],
+                        ])
+                        .stroke(Stroke::new(2., Color32::LIGHT_BLUE))
+                        .show_control_points(true),
+                    );
 
                     // Text
                     plot_ui.add(
                         Text::new(pos2(-12., -6.), "^ Move the cursor here ^")
                             .anchor(Align2::CENTER_TOP),
                     );
+
+                    // Draggable threshold
+                    plot_ui.add(
+                        HLine::new(*threshold)
+                            .stroke(Stroke::new(1., Color32::LIGHT_RED))
+                            .label("drag me")
+                            .draggable("threshold"),
+                    );
                 });
+            if let Some((_, y)) = response.dragged_line {
+                *threshold = y;
+            }
         });
     }
 